@@ -0,0 +1,185 @@
+//! Fetching and decoding the program's accounts.
+//!
+//! Listings filter on the account discriminator plus one key field with
+//! `memcmp`, so the cluster does the filtering and only matching accounts
+//! are transferred.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use journal_dapp::states::*;
+use solana_rpc_client_api::filter::Memcmp;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::connection::{ClientError, JournalConnection};
+use crate::pda;
+
+/// Offset of `JournalEntry::owner`, right after the discriminator.
+pub const ENTRY_OWNER_OFFSET: usize = 8;
+
+/// Decodes `account` as a `T`, checking that the program owns it before
+/// looking at its discriminator; anyone can write matching bytes into an
+/// account of their own program.
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, account: &Account) -> Result<T, ClientError> {
+    if account.owner != journal_dapp::ID {
+        return Err(ClientError::InvalidAccount(*address));
+    }
+    T::try_deserialize(&mut &account.data[..]).map_err(|_| ClientError::InvalidAccount(*address))
+}
+
+/// The `T` at `address`, or `None` if no account lives there.
+pub fn fetch<T: AccountDeserialize>(
+    connection: &(impl JournalConnection + ?Sized),
+    address: &Pubkey,
+) -> Result<Option<T>, ClientError> {
+    connection
+        .get_account(address)?
+        .map(|account| decode(address, &account))
+        .transpose()
+}
+
+/// Every `T` whose 32 bytes at `offset` equal `key`.
+pub fn fetch_by_key<T: AccountDeserialize + Discriminator>(
+    connection: &(impl JournalConnection + ?Sized),
+    offset: usize,
+    key: &Pubkey,
+) -> Result<Vec<(Pubkey, T)>, ClientError> {
+    let filters = vec![
+        Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR),
+        Memcmp::new_base58_encoded(offset, key.as_ref()),
+    ];
+    connection
+        .get_program_accounts(filters)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&address, &account)?)))
+        .collect()
+}
+
+/// Live entries of `owner`; archived entries are left out, as they only
+/// wait to be restored or purged. Entries still in a legacy layout share the
+/// discriminator but are skipped; see `migrate_entry`.
+pub fn entries_by_owner(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, JournalEntry)>, ClientError> {
+    let mut journal_entries = all_entries_by_owner(connection, owner)?;
+    journal_entries.retain(|(_, journal_entry)| journal_entry.archived_at.is_none());
+    Ok(journal_entries)
+}
+
+/// Every entry of `owner`, archived ones included; check `archived_at` to
+/// tell them apart.
+pub fn all_entries_by_owner(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, JournalEntry)>, ClientError> {
+    let filters = vec![
+        Memcmp::new_base58_encoded(0, &JournalEntry::DISCRIMINATOR),
+        Memcmp::new_base58_encoded(ENTRY_OWNER_OFFSET, owner.as_ref()),
+    ];
+    Ok(connection
+        .get_program_accounts(filters)?
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let journal_entry = decode::<JournalEntry>(&address, &account).ok()?;
+            (journal_entry.version == ENTRY_VERSION).then_some((address, journal_entry))
+        })
+        .collect())
+}
+
+/// Entries of `owner` tagged `tag`, in the order they were tagged, read
+/// through the tag's `TagIndex` so only those entries are fetched. Archived
+/// entries keep their tags and are included; check `archived_at`.
+pub fn entries_by_tag(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+    tag: &str,
+) -> Result<Vec<(Pubkey, JournalEntry)>, ClientError> {
+    let Some(tag_index) = fetch::<TagIndex>(connection, &pda::tag_index(owner, tag))? else {
+        return Ok(Vec::new());
+    };
+    let mut journal_entries = Vec::with_capacity(tag_index.entries.len());
+    for entry in tag_index.entries {
+        if let Some(journal_entry) = fetch::<JournalEntry>(connection, &entry)? {
+            journal_entries.push((entry, journal_entry));
+        }
+    }
+    Ok(journal_entries)
+}
+
+/// Reader grants on `entry`.
+pub fn entry_readers(
+    connection: &(impl JournalConnection + ?Sized),
+    entry: &Pubkey,
+) -> Result<Vec<(Pubkey, EntryReader)>, ClientError> {
+    fetch_by_key(connection, 8, entry)
+}
+
+/// Revisions of `entry` that have not been pruned, oldest first.
+pub fn entry_revisions(
+    connection: &(impl JournalConnection + ?Sized),
+    entry: &Pubkey,
+) -> Result<Vec<(Pubkey, JournalRevision)>, ClientError> {
+    let mut revisions = fetch_by_key::<JournalRevision>(connection, 8, entry)?;
+    revisions.sort_by_key(|(_, revision)| revision.revision);
+    Ok(revisions)
+}
+
+/// Pages of a long-form entry in reading order.
+pub fn entry_pages(
+    connection: &(impl JournalConnection + ?Sized),
+    journal_entry: &JournalEntry,
+) -> Result<Vec<EntryPage>, ClientError> {
+    let entry = pda::entry_of(journal_entry);
+    journal_entry
+        .pages
+        .iter()
+        .map(|page_id| {
+            let address = pda::entry_page(&entry, *page_id);
+            fetch(connection, &address)?.ok_or(ClientError::InvalidAccount(address))
+        })
+        .collect()
+}
+
+/// Delegates `owner` has appointed.
+pub fn delegates_of(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, JournalDelegate)>, ClientError> {
+    fetch_by_key(connection, 8, owner)
+}
+
+/// Open transfer offers made by `owner`.
+pub fn offers_from(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, TransferOffer)>, ClientError> {
+    fetch_by_key(connection, 8 + 32, owner)
+}
+
+/// Open transfer offers waiting for `recipient` to accept.
+pub fn offers_to(
+    connection: &(impl JournalConnection + ?Sized),
+    recipient: &Pubkey,
+) -> Result<Vec<(Pubkey, TransferOffer)>, ClientError> {
+    fetch_by_key(connection, 8 + 64, recipient)
+}
+
+/// Index of the `JournalPage` listing `entry`, found by walking the owner's
+/// pages; `None` if no page lists it.
+pub fn find_entry_page(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+    entry: &Pubkey,
+) -> Result<Option<u64>, ClientError> {
+    let Some(user_journal) = fetch::<UserJournal>(connection, &pda::user_journal(owner))? else {
+        return Ok(None);
+    };
+    for index in 0..user_journal.page_count() {
+        if let Some(page) = fetch::<JournalPage>(connection, &pda::journal_page(owner, index))? {
+            if page.entries.contains(entry) {
+                return Ok(Some(index));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,63 @@
+//! Checks a local file against a notarized journal entry.
+//!
+//! Usage: journal-verify <FILE> <ENTRY_ADDRESS> [--url <RPC_URL>]
+
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use journal_client::notary::verify_file;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+const USAGE: &str = "usage: journal-verify <FILE> <ENTRY_ADDRESS> [--url <RPC_URL>]";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (file, entry, url) = match args.as_slice() {
+        [file, entry] => (file, entry, DEFAULT_URL),
+        [file, entry, flag, url] if flag == "--url" => (file, entry, url.as_str()),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let Ok(entry) = Pubkey::from_str(entry) else {
+        eprintln!("invalid entry address: {entry}");
+        return ExitCode::from(2);
+    };
+
+    let rpc = RpcClient::new(url.to_string());
+    match verify_file(&rpc, &entry, Path::new(file)) {
+        Ok(verification) => {
+            println!("document: {}", hex(&verification.document_hash));
+            println!("recorded: {}", hex(&verification.recorded_hash));
+            println!(
+                "recorded at slot {} (unix time {})",
+                verification.slot, verification.recorded_at
+            );
+            if let Some(uri) = &verification.uri {
+                println!("uri: {uri}");
+            }
+            if let Some(mime) = &verification.mime {
+                println!("mime: {mime}");
+            }
+            if verification.matches {
+                println!("MATCH");
+                ExitCode::SUCCESS
+            } else {
+                println!("MISMATCH");
+                ExitCode::FAILURE
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
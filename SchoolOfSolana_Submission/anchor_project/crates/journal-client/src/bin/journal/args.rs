@@ -0,0 +1,82 @@
+//! A small command-line parser. Options may appear anywhere, before or
+//! after the subcommand, as `--name value`, `--name=value` or `-n value`.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+pub struct Args {
+    words: Vec<String>,
+}
+
+impl Args {
+    pub fn new(words: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            words: words.into_iter().collect(),
+        }
+    }
+
+    /// Removes a boolean flag, returning whether it was given.
+    pub fn flag(&mut self, names: &[&str]) -> bool {
+        let before = self.words.len();
+        self.words.retain(|word| !names.contains(&word.as_str()));
+        self.words.len() != before
+    }
+
+    /// Removes every occurrence of an option that takes a value.
+    pub fn options(&mut self, names: &[&str]) -> Result<Vec<String>, UsageError> {
+        let mut values = Vec::new();
+        let mut i = 0;
+        while i < self.words.len() {
+            let word = &self.words[i];
+            if let Some((_, value)) = word.split_once('=').filter(|(name, _)| names.contains(name)) {
+                values.push(value.to_string());
+                self.words.remove(i);
+            } else if names.contains(&word.as_str()) {
+                if i + 1 == self.words.len() {
+                    return Err(UsageError(format!("{word} needs a value")));
+                }
+                values.push(self.words.remove(i + 1));
+                self.words.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Removes an option that may be given at most once.
+    pub fn option(&mut self, names: &[&str]) -> Result<Option<String>, UsageError> {
+        let mut values = self.options(names)?;
+        if values.len() > 1 {
+            return Err(UsageError(format!("{} given more than once", names[0])));
+        }
+        Ok(values.pop())
+    }
+
+    /// Removes the next positional argument, if any.
+    pub fn next(&mut self) -> Option<String> {
+        let position = self.words.iter().position(|word| !word.starts_with('-') || word == "-")?;
+        Some(self.words.remove(position))
+    }
+
+    /// Removes the next positional argument, naming it in the error if missing.
+    pub fn required(&mut self, name: &str) -> Result<String, UsageError> {
+        self.next().ok_or_else(|| UsageError(format!("missing <{name}>")))
+    }
+
+    /// Fails on anything that was not consumed.
+    pub fn finish(self) -> Result<(), UsageError> {
+        match self.words.first() {
+            Some(word) => Err(UsageError(format!("unexpected argument: {word}"))),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Where the CLI finds its keypair and cluster.
+//!
+//! Each setting comes from the first of: its command-line option, its
+//! environment variable, the Solana CLI config file, the Solana CLI default.
+
+use std::path::{Path, PathBuf};
+
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+const KEYPAIR_VAR: &str = "JOURNAL_KEYPAIR";
+const URL_VAR: &str = "JOURNAL_URL";
+
+pub struct Config {
+    pub keypair_path: PathBuf,
+    pub url: String,
+}
+
+impl Config {
+    pub fn resolve(keypair: Option<String>, url: Option<String>, config_file: Option<String>) -> Result<Self, String> {
+        let home = std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+        let config_file = match config_file {
+            Some(path) => Some(PathBuf::from(path)),
+            None => Some(home.join(".config/solana/cli/config.yml")).filter(|path| path.exists()),
+        };
+        let (file_url, file_keypair) = match &config_file {
+            Some(path) => read_cli_config(path)?,
+            None => (None, None),
+        };
+
+        let keypair_path = keypair
+            .or_else(|| std::env::var(KEYPAIR_VAR).ok())
+            .or(file_keypair)
+            .map(|path| expand_home(&path, &home))
+            .unwrap_or_else(|| home.join(".config/solana/id.json"));
+        let url = url
+            .or_else(|| std::env::var(URL_VAR).ok())
+            .or(file_url)
+            .map_or_else(|| DEFAULT_URL.to_string(), |url| cluster_url(&url));
+        Ok(Self { keypair_path, url })
+    }
+
+    pub fn keypair(&self) -> Result<Keypair, String> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|err| format!("cannot read keypair {}: {err}", self.keypair_path.display()))
+    }
+}
+
+/// `json_rpc_url` and `keypair_path` from a Solana CLI config file. The file
+/// is flat YAML, so each setting is one `key: value` line.
+fn read_cli_config(path: &Path) -> Result<(Option<String>, Option<String>), String> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|err| format!("cannot read config {}: {err}", path.display()))?;
+    let setting = |key: &str| {
+        contents.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+            Some(value.trim_matches(|c| c == '"' || c == '\'').to_string()).filter(|value| !value.is_empty())
+        })
+    };
+    Ok((setting("json_rpc_url"), setting("keypair_path")))
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Expands the monikers the Solana CLI accepts for `--url`.
+fn cluster_url(url: &str) -> String {
+    match url {
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "t" | "testnet" => "https://api.testnet.solana.com",
+        "l" | "localhost" => DEFAULT_URL,
+        url => url,
+    }
+    .to_string()
+}
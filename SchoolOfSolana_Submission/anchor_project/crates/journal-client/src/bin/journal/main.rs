@@ -0,0 +1,536 @@
+//! Command-line journal backed by the `journal_dapp` program.
+//!
+//! Run `journal --help` for usage.
+
+mod args;
+mod config;
+
+use std::fs::OpenOptions;
+use std::io::{IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use journal_client::accounts::{
+    all_entries_by_owner, entries_by_owner, entries_by_tag, entry_readers, entry_revisions, fetch, find_entry_page,
+};
+use journal_client::compose::{self, ComposeError};
+use journal_client::connection::{ClientError, JournalConnection};
+use journal_client::crypto::EntryKey;
+use journal_client::export::{self, rfc3339, ExportError, ExportedEntry, Format};
+use journal_client::{instructions, pda};
+use journal_dapp::states::*;
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use args::{Args, UsageError};
+use config::Config;
+
+const USAGE: &str = "\
+usage: journal [OPTIONS] <COMMAND>
+
+commands:
+  create <TITLE> [-m TEXT] [-t TAG]... [-n NOTEBOOK]    write a new entry
+  show <TITLE> [-n NOTEBOOK]                           print an entry
+  list [-t TAG] [-n NOTEBOOK] [--archived]             list entries, oldest first
+  edit <TITLE> [-m TEXT] [-t TAG]... [--no-tags] [-n NOTEBOOK]
+                                                       rewrite an entry
+  rm <TITLE> [-n NOTEBOOK] [-y]                        delete an entry
+  export [-f json|markdown] [-o PATH]                  back up every entry
+  import <PATH> [--dry-run]                            create the entries of an export,
+                                                       skipping titles already taken
+
+options:
+  -k, --keypair <PATH>  signer keypair [env: JOURNAL_KEYPAIR]
+  -u, --url <URL>       RPC URL or m, d, t, l [env: JOURNAL_URL]
+  -C, --config <PATH>   Solana CLI config file to read defaults from
+      --json            print JSON for scripts
+  -h, --help            print this help
+
+Without -m, text is read from stdin when it is not a terminal and from
+$VISUAL or $EDITOR otherwise; `-m -` always reads stdin.";
+
+enum Error {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<UsageError> for Error {
+    fn from(err: UsageError) -> Self {
+        Error::Usage(err.0)
+    }
+}
+
+impl From<String> for Error {
+    fn from(reason: String) -> Self {
+        Error::Failed(reason)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Failed(err.to_string())
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(err: ClientError) -> Self {
+        // The program's own message is more useful than the error code.
+        if let ClientError::Transaction { logs, .. } = &err {
+            let message = logs.iter().find_map(|line| line.split_once("Error Message: "));
+            if let Some((_, message)) = message {
+                return Error::Failed(message.trim_end_matches('.').to_string());
+            }
+        }
+        Error::Failed(err.to_string())
+    }
+}
+
+impl From<ExportError> for Error {
+    fn from(err: ExportError) -> Self {
+        Error::Failed(err.to_string())
+    }
+}
+
+impl From<ComposeError> for Error {
+    fn from(err: ComposeError) -> Self {
+        match err {
+            ComposeError::Client(err) => err.into(),
+            err => Error::Failed(err.to_string()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::new(std::env::args().skip(1))) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::Usage(reason)) => {
+            eprintln!("error: {reason}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(Error::Failed(reason)) => {
+            eprintln!("error: {reason}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Args) -> Result<(), Error> {
+    if args.flag(&["-h", "--help"]) {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let json = args.flag(&["--json"]);
+    let keypair = args.option(&["-k", "--keypair"])?;
+    let url = args.option(&["-u", "--url"])?;
+    let config = Config::resolve(keypair, url, args.option(&["-C", "--config"])?)?;
+    let command: fn(&Cli, Args) -> Result<(), Error> = match args.required("COMMAND")?.as_str() {
+        "create" => Cli::create,
+        "show" => Cli::show,
+        "list" => Cli::list,
+        "edit" => Cli::edit,
+        "rm" => Cli::rm,
+        "export" => Cli::export,
+        "import" => Cli::import,
+        command => return Err(Error::Usage(format!("unknown command: {command}"))),
+    };
+
+    let cli = Cli {
+        owner: config.keypair()?,
+        rpc: RpcClient::new_with_commitment(config.url, CommitmentConfig::confirmed()),
+        json,
+    };
+    command(&cli, args)
+}
+
+struct Cli {
+    rpc: RpcClient,
+    owner: Keypair,
+    json: bool,
+}
+
+impl Cli {
+    fn create(&self, mut args: Args) -> Result<(), Error> {
+        let message = args.option(&["-m", "--message"])?;
+        let tags = args.options(&["-t", "--tag"])?;
+        let notebook = notebook_option(&mut args)?;
+        let title = args.required("TITLE")?;
+        args.finish()?;
+
+        let text = match message {
+            Some(message) => message_text(message)?,
+            None => input_text("")?,
+        };
+        let journal_entry = compose::create_entry(&self.rpc, &self.owner, notebook, &title, &text, &tags)?;
+        self.report("created", &journal_entry);
+        Ok(())
+    }
+
+    fn show(&self, mut args: Args) -> Result<(), Error> {
+        let notebook = notebook_option(&mut args)?;
+        let title = args.required("TITLE")?;
+        args.finish()?;
+
+        let journal_entry = self.find(&title, notebook)?;
+        let text = self.text(&journal_entry)?;
+        if self.json {
+            let mut value = entry_json(&journal_entry);
+            value["text"] = json!(text);
+            println!("{value:#}");
+            return Ok(());
+        }
+        println!("{}", journal_entry.title);
+        println!("address:  {}", pda::entry_of(&journal_entry));
+        println!("created:  {}", format_time(journal_entry.created_at));
+        if journal_entry.updated_at != journal_entry.created_at {
+            println!("updated:  {}", format_time(journal_entry.updated_at));
+        }
+        if !journal_entry.tags.is_empty() {
+            println!("tags:     {}", journal_entry.tags.join(", "));
+        }
+        if journal_entry.notebook != 0 {
+            println!("notebook: {}", journal_entry.notebook);
+        }
+        if let Some(seal) = &journal_entry.seal {
+            println!("sealed:   {}", format_time(seal.sealed_at));
+        }
+        if let Some(archived_at) = journal_entry.archived_at {
+            println!("archived: {}", format_time(archived_at));
+        }
+        if let Some(notarization) = &journal_entry.notarization {
+            println!("sha256:   {}", hex(&notarization.sha256));
+        }
+        if !text.is_empty() {
+            println!("\n{text}");
+        }
+        Ok(())
+    }
+
+    fn list(&self, mut args: Args) -> Result<(), Error> {
+        let tag = args.option(&["-t", "--tag"])?;
+        let notebook = args.option(&["-n", "--notebook"])?.map(|id| parse_notebook(&id)).transpose()?;
+        let archived = args.flag(&["--archived"]);
+        args.finish()?;
+
+        let owner = self.owner.pubkey();
+        let listed = match &tag {
+            Some(tag) => entries_by_tag(&self.rpc, &owner, tag)?,
+            None if archived => all_entries_by_owner(&self.rpc, &owner)?,
+            None => entries_by_owner(&self.rpc, &owner)?,
+        };
+        let mut journal_entries: Vec<JournalEntry> = listed
+            .into_iter()
+            .map(|(_, journal_entry)| journal_entry)
+            .filter(|journal_entry| archived || journal_entry.archived_at.is_none())
+            .filter(|journal_entry| notebook.is_none_or(|notebook| journal_entry.notebook == notebook))
+            .collect();
+        journal_entries.sort_by(|a, b| (a.created_at, &a.title).cmp(&(b.created_at, &b.title)));
+
+        if self.json {
+            let values: Vec<Value> = journal_entries.iter().map(entry_json).collect();
+            println!("{:#}", Value::Array(values));
+            return Ok(());
+        }
+        for journal_entry in &journal_entries {
+            let mut line = format!("{}  {}", format_time(journal_entry.created_at), journal_entry.title);
+            for tag in &journal_entry.tags {
+                line.push_str(&format!(" #{tag}"));
+            }
+            if journal_entry.archived_at.is_some() {
+                line.push_str(" (archived)");
+            }
+            println!("{line}");
+        }
+        Ok(())
+    }
+
+    fn edit(&self, mut args: Args) -> Result<(), Error> {
+        let message = args.option(&["-m", "--message"])?;
+        let tags = args.options(&["-t", "--tag"])?;
+        let no_tags = args.flag(&["--no-tags"]);
+        let notebook = notebook_option(&mut args)?;
+        let title = args.required("TITLE")?;
+        args.finish()?;
+        if no_tags && !tags.is_empty() {
+            return Err(Error::Usage("--no-tags conflicts with --tag".to_string()));
+        }
+
+        let journal_entry = self.find(&title, notebook)?;
+        let old_text = compose::entry_text(&self.rpc, &journal_entry)?;
+        let text = match message {
+            Some(message) => message_text(message)?,
+            None => input_text(&old_text)?,
+        };
+        let tags = (no_tags || !tags.is_empty()).then_some(tags);
+        if text == old_text && tags.as_ref().is_none_or(|tags| *tags == journal_entry.tags) {
+            self.report("unchanged", &journal_entry);
+            return Ok(());
+        }
+        let journal_entry = compose::rewrite_entry(&self.rpc, &self.owner, &journal_entry, &text, tags.as_deref())?;
+        self.report("updated", &journal_entry);
+        Ok(())
+    }
+
+    fn rm(&self, mut args: Args) -> Result<(), Error> {
+        let yes = args.flag(&["-y", "--yes"]);
+        let notebook = notebook_option(&mut args)?;
+        let title = args.required("TITLE")?;
+        args.finish()?;
+
+        let journal_entry = self.find(&title, notebook)?;
+        if !yes && !confirm(&format!("delete \"{}\"?", journal_entry.title))? {
+            return Err(Error::Failed("not deleted".to_string()));
+        }
+        let owner = self.owner.pubkey();
+        let entry = pda::entry_of(&journal_entry);
+        let page_index = find_entry_page(&self.rpc, &owner, &entry)?
+            .ok_or_else(|| format!("entry {entry} is not listed in the journal"))?;
+        let readers: Vec<Pubkey> = entry_readers(&self.rpc, &entry)?
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+
+        let mut batch = Vec::new();
+        if let Some(feed_page) = journal_entry.feed_page {
+            batch.push(instructions::set_visibility(&journal_entry, feed_page, Visibility::Private));
+        }
+        let revisions: Vec<Pubkey> = entry_revisions(&self.rpc, &entry)?
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+        batch.push(instructions::delete_entry(&owner, &journal_entry, page_index, &readers, &revisions));
+        JournalConnection::send(&self.rpc, &batch, &[&self.owner])?;
+        self.report("deleted", &journal_entry);
+        Ok(())
+    }
+
+    fn export(&self, mut args: Args) -> Result<(), Error> {
+        let format = match args.option(&["-f", "--format"])?.as_deref() {
+            None | Some("json") => Format::Json,
+            Some("markdown" | "md") => Format::Markdown,
+            Some(format) => return Err(Error::Usage(format!("unknown format: {format}"))),
+        };
+        let output = args.option(&["-o", "--output"])?.filter(|path| path != "-");
+        args.finish()?;
+        if format == Format::Markdown && output.is_none() {
+            return Err(Error::Usage("a markdown export needs -o <DIR>".to_string()));
+        }
+
+        let export = export::export_entries(&self.rpc, &self.owner.pubkey())?;
+        let skipped: Vec<String> = export.skipped.iter().map(Pubkey::to_string).collect();
+        let Some(output) = output else {
+            print!("{}", export::to_json(&export.entries));
+            if !skipped.is_empty() {
+                eprintln!("skipped {} encrypted or notarized entries", skipped.len());
+            }
+            return Ok(());
+        };
+        export::write_export(Path::new(&output), format, &export.entries)?;
+        if self.json {
+            let report = json!({ "exported": export.entries.len(), "skipped": skipped, "path": output });
+            println!("{report:#}");
+        } else {
+            println!("exported {} entries to {output}", export.entries.len());
+            if !skipped.is_empty() {
+                println!("skipped {} encrypted or notarized entries", skipped.len());
+            }
+        }
+        Ok(())
+    }
+
+    fn import(&self, mut args: Args) -> Result<(), Error> {
+        let dry_run = args.flag(&["--dry-run"]);
+        let path = args.required("PATH")?;
+        args.finish()?;
+
+        let entries = if path == "-" {
+            export::from_json(&read_stdin()?).map_err(|reason| format!("stdin: {reason}"))?
+        } else {
+            export::read_export(Path::new(&path))?
+        };
+        let plan = export::plan_import(&self.rpc, &self.owner.pubkey(), entries)?;
+        let titles = |entries: &[ExportedEntry]| entries.iter().map(|entry| entry.title.clone()).collect::<Vec<_>>();
+        let unfiled: Vec<Value> = plan
+            .unfiled
+            .iter()
+            .map(|(title, notebook)| json!({ "title": title, "notebook": notebook }))
+            .collect();
+        if !self.json {
+            for (title, notebook) in &plan.unfiled {
+                println!("note    {title}: notebook {notebook} does not exist, importing it outside any notebook");
+            }
+        }
+        if dry_run {
+            if self.json {
+                let report = json!({
+                    "create": titles(&plan.create),
+                    "duplicates": titles(&plan.duplicates),
+                    "unfiled": unfiled,
+                });
+                println!("{report:#}");
+            } else {
+                for entry in &plan.create {
+                    println!("create  {}", entry.title);
+                }
+                for entry in &plan.duplicates {
+                    println!("skip    {} (title taken)", entry.title);
+                }
+                println!("dry run: {} to create, {} duplicates", plan.create.len(), plan.duplicates.len());
+            }
+            return Ok(());
+        }
+
+        if !self.json {
+            for entry in &plan.duplicates {
+                println!("skipped {} (title taken)", entry.title);
+            }
+        }
+        let mut created = Vec::new();
+        let result = export::import_entries(&self.rpc, &self.owner, &plan, |entry| {
+            if !self.json {
+                println!("created {}", entry.title);
+            }
+            created.push(entry.title.clone());
+        });
+        if self.json {
+            let report = json!({ "created": created, "duplicates": titles(&plan.duplicates), "unfiled": unfiled });
+            println!("{report:#}");
+        }
+        Ok(result?)
+    }
+
+    /// The owner's entry titled `title`.
+    fn find(&self, title: &str, notebook: u32) -> Result<JournalEntry, Error> {
+        let address = pda::entry(&self.owner.pubkey(), title, notebook);
+        let journal_entry = fetch(&self.rpc, &address)?;
+        Ok(journal_entry.ok_or_else(|| format!("no entry titled \"{title}\""))?)
+    }
+
+    /// Plaintext of `journal_entry`, decrypting it with the owner's key, or
+    /// for envelope entries with the content key of the owner's own grant.
+    fn text(&self, journal_entry: &JournalEntry) -> Result<String, Error> {
+        let Some(content) = &journal_entry.encrypted else {
+            return Ok(compose::entry_text(&self.rpc, journal_entry)?);
+        };
+        let key = match content.scheme {
+            EncryptionScheme::XChaCha20Poly1305 => EntryKey::derive(&self.owner),
+            EncryptionScheme::XChaCha20Poly1305Envelope => {
+                let grant = pda::entry_reader(&pda::entry_of(journal_entry), &self.owner.pubkey());
+                let grant = fetch::<EntryReader>(&self.rpc, &grant)?
+                    .ok_or_else(|| format!("no reader grant for {} on this entry", self.owner.pubkey()))?;
+                EntryKey::unwrap_with(&self.owner, &grant.wrapped_key)
+            }
+        };
+        let key = key.map_err(|err| err.to_string())?;
+        Ok(key.decrypt(content).map_err(|err| err.to_string())?)
+    }
+
+    fn report(&self, action: &str, journal_entry: &JournalEntry) {
+        let address = pda::entry_of(journal_entry);
+        if self.json {
+            println!("{:#}", json!({ "action": action, "title": journal_entry.title, "address": address.to_string() }));
+        } else {
+            println!("{action} \"{}\" ({address})", journal_entry.title);
+        }
+    }
+}
+
+fn entry_json(journal_entry: &JournalEntry) -> Value {
+    json!({
+        "title": journal_entry.title,
+        "address": pda::entry_of(journal_entry).to_string(),
+        "created_at": journal_entry.created_at,
+        "updated_at": journal_entry.updated_at,
+        "tags": journal_entry.tags,
+        "notebook": journal_entry.notebook,
+        "archived": journal_entry.archived_at.is_some(),
+        "sealed": journal_entry.seal.is_some(),
+        "encrypted": journal_entry.encrypted.is_some(),
+        "pages": journal_entry.pages.len(),
+    })
+}
+
+fn notebook_option(args: &mut Args) -> Result<u32, Error> {
+    args.option(&["-n", "--notebook"])?
+        .map_or(Ok(0), |id| parse_notebook(&id))
+}
+
+fn parse_notebook(id: &str) -> Result<u32, Error> {
+    id.parse().map_err(|_| Error::Usage(format!("invalid notebook id: {id}")))
+}
+
+/// Text given with `-m`, where `-` means stdin.
+fn message_text(message: String) -> Result<String, Error> {
+    if message == "-" {
+        read_stdin()
+    } else {
+        Ok(message)
+    }
+}
+
+/// Text piped to stdin, or typed in an editor starting from `initial`.
+fn input_text(initial: &str) -> Result<String, Error> {
+    if std::io::stdin().is_terminal() {
+        edit_text(initial)
+    } else {
+        read_stdin()
+    }
+}
+
+fn read_stdin() -> Result<String, Error> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    Ok(text.trim_end_matches('\n').to_string())
+}
+
+/// Opens `$VISUAL` or `$EDITOR` on a temporary file holding `initial`.
+fn edit_text(initial: &str) -> Result<String, Error> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("journal-{}.md", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?
+        .write_all(initial.as_bytes())?;
+
+    // The editor may carry arguments, as in `code --wait`.
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("journal")
+        .arg(&path)
+        .status();
+    let text = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    match status {
+        Ok(status) if status.success() => Ok(text?.trim_end_matches('\n').to_string()),
+        Ok(status) => Err(Error::Failed(format!("{editor} exited with {status}"))),
+        Err(err) => Err(Error::Failed(format!("cannot run {editor}: {err}"))),
+    }
+}
+
+/// Asks on the terminal; refuses outright when there is no terminal to ask.
+fn confirm(question: &str) -> Result<bool, Error> {
+    if !std::io::stdin().is_terminal() {
+        return Err(Error::Usage("pass --yes to confirm when stdin is not a terminal".to_string()));
+    }
+    eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// `unix_timestamp` as `YYYY-MM-DD HH:MM` in UTC.
+fn format_time(unix_timestamp: i64) -> String {
+    let time = rfc3339(unix_timestamp);
+    format!("{} {}", &time[..10], &time[11..16])
+}
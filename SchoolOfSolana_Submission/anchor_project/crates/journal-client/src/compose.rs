@@ -0,0 +1,176 @@
+//! Writing an entry's full text, however long.
+//!
+//! A transaction must fit in one packet, so the text is laid out with
+//! `LongForm::split_to` using the largest message and pages that still fit
+//! next to each instruction's accounts, title and tags. The message goes
+//! through `create_entry` or `update_entry` and every page through its own
+//! page instruction.
+
+use std::fmt;
+
+use journal_dapp::states::*;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+use crate::accounts::{self, fetch};
+use crate::connection::{ClientError, JournalConnection};
+use crate::pages::{join_pages, LongForm};
+use crate::{instructions, pda};
+
+#[derive(Debug)]
+pub enum ComposeError {
+    /// An RPC request or transaction failed.
+    Client(ClientError),
+    /// The text needs more pages than an entry can hold.
+    TooLong { pages: usize },
+    /// Encrypted and notarized entries have no plaintext to rewrite.
+    NotPlaintext(Pubkey),
+}
+
+impl fmt::Display for ComposeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComposeError::Client(err) => err.fmt(f),
+            ComposeError::TooLong { pages } => {
+                write!(f, "text needs {pages} pages, but an entry holds at most {MAX_PAGES}")
+            }
+            ComposeError::NotPlaintext(entry) => write!(f, "entry {entry} is encrypted or notarized"),
+        }
+    }
+}
+
+impl std::error::Error for ComposeError {}
+
+impl From<ClientError> for ComposeError {
+    fn from(err: ClientError) -> Self {
+        ComposeError::Client(err)
+    }
+}
+
+/// Bytes of text that fit in a transaction carrying `instruction`, built
+/// with empty text.
+fn room(instruction: Instruction, payer: &Pubkey) -> usize {
+    let transaction = Transaction::new_with_payer(&[instruction], Some(payer));
+    let size = bincode::serialized_size(&transaction).expect("transactions always serialize") as usize;
+    // The instruction data length is a compact-u16 that grows by a byte once
+    // the text is added.
+    PACKET_DATA_SIZE.saturating_sub(size + 1)
+}
+
+/// Splits `text` with `message_room` bytes for the message and pages sized
+/// for `journal_entry`.
+fn layout(journal_entry: &JournalEntry, message_room: usize, text: &str) -> Result<LongForm, ComposeError> {
+    let page_room = room(instructions::replace_page(journal_entry, 0, ""), &journal_entry.owner);
+    let long_form = LongForm::split_to(text, message_room.min(MESSAGE_LENGTH), page_room.min(PAGE_LENGTH));
+    if long_form.pages.len() > MAX_PAGES {
+        return Err(ComposeError::TooLong {
+            pages: long_form.pages.len(),
+        });
+    }
+    Ok(long_form)
+}
+
+fn fetch_entry(
+    connection: &(impl JournalConnection + ?Sized),
+    journal_entry: &JournalEntry,
+) -> Result<JournalEntry, ClientError> {
+    let address = pda::entry_of(journal_entry);
+    fetch(connection, &address)?.ok_or(ClientError::InvalidAccount(address))
+}
+
+/// Creates an entry holding `text`, appending pages for whatever does not
+/// fit in its message, and returns it as stored.
+pub fn create_entry(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Keypair,
+    notebook: u32,
+    title: &str,
+    text: &str,
+    tags: &[String],
+) -> Result<JournalEntry, ComposeError> {
+    let owner_key = owner.pubkey();
+    let page_index = fetch::<UserJournal>(connection, &pda::user_journal(&owner_key))?
+        .map_or(0, |user_journal| user_journal.current_page());
+    let mut draft = JournalEntry::new(owner_key, title.to_string(), String::new(), 0);
+    draft.notebook = notebook;
+    let message_room = room(
+        instructions::create_entry(&owner_key, page_index, notebook, title, "", tags),
+        &owner_key,
+    );
+    let long_form = layout(&draft, message_room, text)?;
+
+    let create = instructions::create_entry(&owner_key, page_index, notebook, title, &long_form.message, tags);
+    connection.send(&[create], &[owner])?;
+    let mut journal_entry = fetch_entry(connection, &draft)?;
+    for page in &long_form.pages {
+        connection.send(&[instructions::append_page(&journal_entry, page)], &[owner])?;
+        journal_entry.pages.push(journal_entry.next_page_id);
+        journal_entry.next_page_id += 1;
+    }
+    Ok(fetch_entry(connection, &journal_entry)?)
+}
+
+/// Replaces the text of `journal_entry`, and its tags when `tags` is given,
+/// sending only the instructions whose part of the text changed. Returns the
+/// entry as stored.
+pub fn rewrite_entry(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Keypair,
+    journal_entry: &JournalEntry,
+    text: &str,
+    tags: Option<&[String]>,
+) -> Result<JournalEntry, ComposeError> {
+    if journal_entry.encrypted.is_some() || journal_entry.notarization.is_some() {
+        return Err(ComposeError::NotPlaintext(pda::entry_of(journal_entry)));
+    }
+    let owner_key = owner.pubkey();
+    let mut message_room = room(
+        instructions::update_entry(&owner_key, journal_entry, "", tags),
+        &owner_key,
+    );
+    // Keep a full message as it is when the text still starts with it, so
+    // the pages after it do not all shift.
+    let message = &journal_entry.message;
+    if !journal_entry.pages.is_empty() && text.starts_with(message.as_str()) && message.len() <= message_room {
+        message_room = message.len();
+    }
+    let long_form = layout(journal_entry, message_room, text)?;
+    let old_pages = accounts::entry_pages(connection, journal_entry)?;
+
+    let tags_changed = tags.is_some_and(|tags| tags != journal_entry.tags.as_slice());
+    if long_form.message != journal_entry.message || tags_changed {
+        let update = instructions::update_entry(&owner_key, journal_entry, &long_form.message, tags);
+        connection.send(&[update], &[owner])?;
+    }
+    let mut current = journal_entry.clone();
+    for (i, page) in long_form.pages.iter().enumerate() {
+        match old_pages.get(i) {
+            Some(old) if old.content == *page => {}
+            Some(old) => {
+                connection.send(&[instructions::replace_page(&current, old.page_id, page)], &[owner])?;
+            }
+            None => {
+                connection.send(&[instructions::append_page(&current, page)], &[owner])?;
+                current.next_page_id += 1;
+            }
+        }
+    }
+    for old in old_pages.iter().skip(long_form.pages.len()) {
+        connection.send(&[instructions::remove_page(&current, old.page_id)], &[owner])?;
+    }
+    Ok(fetch_entry(connection, journal_entry)?)
+}
+
+/// Full text of a plaintext entry: its message followed by its pages.
+pub fn entry_text(
+    connection: &(impl JournalConnection + ?Sized),
+    journal_entry: &JournalEntry,
+) -> Result<String, ClientError> {
+    let pages = accounts::entry_pages(connection, journal_entry)?;
+    let contents: Vec<&str> = pages.iter().map(|page| page.content.as_str()).collect();
+    Ok(join_pages(&journal_entry.message, &contents))
+}
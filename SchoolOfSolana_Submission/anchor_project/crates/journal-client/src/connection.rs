@@ -0,0 +1,112 @@
+//! The link between the client and a cluster.
+//!
+//! Everything in this crate that reads accounts or sends transactions goes
+//! through `JournalConnection`, which is implemented for `RpcClient` and for
+//! the in-process `TestVm`, so the same code runs against a validator and in
+//! unit tests.
+
+use std::fmt;
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::{Error as RpcClientError, ErrorKind};
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_rpc_client_api::request::{RpcError, RpcResponseErrorData};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request failed before any transaction was executed.
+    Rpc(String),
+    /// The transaction was executed and failed.
+    Transaction { error: TransactionError, logs: Vec<String> },
+    /// The account exists but is not of the expected type.
+    InvalidAccount(Pubkey),
+}
+
+impl ClientError {
+    /// Code of the custom program error that failed the transaction, such as
+    /// `u32::from(JournalError::EntrySealed)`.
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            ClientError::Transaction {
+                error: TransactionError::InstructionError(_, InstructionError::Custom(code)),
+                ..
+            } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Rpc(reason) => write!(f, "rpc request failed: {reason}"),
+            ClientError::Transaction { error, .. } => write!(f, "transaction failed: {error}"),
+            ClientError::InvalidAccount(address) => write!(f, "account {address} does not decode"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<RpcClientError> for ClientError {
+    fn from(err: RpcClientError) -> Self {
+        let Some(error) = err.get_transaction_error() else {
+            return ClientError::Rpc(err.to_string());
+        };
+        let logs = match err.kind() {
+            ErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+                ..
+            }) => result.logs.clone().unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        ClientError::Transaction { error, logs }
+    }
+}
+
+pub trait JournalConnection {
+    /// The account at `address`, or `None` if it does not exist.
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>, ClientError>;
+
+    /// Accounts owned by `journal_dapp` whose data matches every filter.
+    fn get_program_accounts(&self, filters: Vec<Memcmp>) -> Result<Vec<(Pubkey, Account)>, ClientError>;
+
+    /// Signs `instructions` with `signers` and executes them as one
+    /// transaction. The first signer pays the fees.
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature, ClientError>;
+}
+
+impl JournalConnection for RpcClient {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>, ClientError> {
+        Ok(self.get_account_with_commitment(address, self.commitment())?.value)
+    }
+
+    fn get_program_accounts(&self, filters: Vec<Memcmp>) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters.into_iter().map(RpcFilterType::Memcmp).collect()),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(self.get_program_accounts_with_config(&journal_dapp::ID, config)?)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature, ClientError> {
+        let payer = signers.first().map(|signer| signer.pubkey());
+        let mut transaction = Transaction::new_with_payer(instructions, payer.as_ref());
+        transaction
+            .try_sign(signers, self.get_latest_blockhash()?)
+            .map_err(|err| ClientError::Rpc(err.to_string()))?;
+        Ok(self.send_and_confirm_transaction(&transaction)?)
+    }
+}
@@ -0,0 +1,221 @@
+//! Client-side encryption of entry messages.
+//!
+//! The entry key is derived from the owner's keypair: the owner signs a fixed
+//! message and the signature is run through HKDF-SHA256. Ed25519 signatures
+//! are deterministic, so the same keypair always yields the same key and
+//! nothing besides the keypair needs to be backed up.
+//!
+//! Entries that are shared use a random content key instead. The key is
+//! sealed to every reader, the owner included, by converting the reader's
+//! Ed25519 public key to X25519 and running an ephemeral Diffie-Hellman
+//! exchange; the result is stored in the reader's `EntryReader` account.
+
+use std::fmt;
+
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use hkdf::Hkdf;
+use journal_dapp::states::{
+    EncryptedContent, EncryptionScheme, MESSAGE_LENGTH, NONCE_LENGTH, WRAPPED_KEY_LENGTH,
+};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+const KEY_DERIVATION_MESSAGE: &[u8] = b"journal-dapp: derive entry encryption key v1";
+const KEY_INFO: &[u8] = b"journal-dapp entry key";
+const WRAP_INFO: &[u8] = b"journal-dapp reader key";
+
+#[derive(Debug)]
+pub enum CryptoError {
+    /// The owner's signer refused to sign the key derivation message.
+    Signing(String),
+    /// The plaintext is longer than `MESSAGE_LENGTH` bytes.
+    MessageTooLong,
+    /// Wrong key, or the ciphertext was tampered with.
+    Decryption,
+    /// The decrypted bytes are not a UTF-8 message.
+    InvalidUtf8,
+    /// Only envelope content keys can be handed to readers.
+    NotShareable,
+    /// The reader's public key is not a valid Ed25519 point.
+    InvalidReaderKey,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::Signing(reason) => write!(f, "could not sign the key derivation message: {reason}"),
+            CryptoError::MessageTooLong => write!(f, "message exceeds {MESSAGE_LENGTH} bytes"),
+            CryptoError::Decryption => write!(f, "wrong key or corrupted ciphertext"),
+            CryptoError::InvalidUtf8 => write!(f, "decrypted message is not valid UTF-8"),
+            CryptoError::NotShareable => write!(f, "key derived from the owner's keypair cannot be shared"),
+            CryptoError::InvalidReaderKey => write!(f, "reader public key is not a valid curve point"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// Symmetric key that encrypts and decrypts entry messages.
+pub struct EntryKey {
+    key: [u8; 32],
+    scheme: EncryptionScheme,
+}
+
+impl EntryKey {
+    /// Derives the owner's entry key from their keypair.
+    pub fn derive<S: Signer + ?Sized>(owner: &S) -> Result<Self, CryptoError> {
+        let signature = owner
+            .try_sign_message(KEY_DERIVATION_MESSAGE)
+            .map_err(|err| CryptoError::Signing(err.to_string()))?;
+        let hkdf = Hkdf::<Sha256>::new(Some(owner.pubkey().as_ref()), signature.as_ref());
+        let mut key = [0; 32];
+        hkdf.expand(KEY_INFO, &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Ok(Self::from_bytes(key))
+    }
+
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self {
+            key,
+            scheme: EncryptionScheme::XChaCha20Poly1305,
+        }
+    }
+
+    /// Generates a random content key for an entry that will be shared.
+    pub fn generate() -> Self {
+        let mut key = [0; 32];
+        OsRng.fill_bytes(&mut key);
+        Self {
+            key,
+            scheme: EncryptionScheme::XChaCha20Poly1305Envelope,
+        }
+    }
+
+    /// Seals the content key to `reader`, producing the `wrapped_key` passed
+    /// to `grant_reader`.
+    pub fn wrap_for(&self, reader: &Pubkey) -> Result<[u8; WRAPPED_KEY_LENGTH], CryptoError> {
+        if self.scheme != EncryptionScheme::XChaCha20Poly1305Envelope {
+            return Err(CryptoError::NotShareable);
+        }
+        let reader_point = CompressedEdwardsY(reader.to_bytes())
+            .decompress()
+            .ok_or(CryptoError::InvalidReaderKey)?
+            .to_montgomery();
+
+        let mut ephemeral = [0; 32];
+        OsRng.fill_bytes(&mut ephemeral);
+        let ephemeral = clamp(ephemeral);
+        let ephemeral_public = ephemeral * X25519_BASEPOINT;
+        let wrapping_key = wrapping_key(&(ephemeral * reader_point), &ephemeral_public, reader)?;
+
+        let mut nonce = [0; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let sealed = wrapping_key
+            .cipher()
+            .encrypt(&XNonce::from(nonce), self.key.as_ref())
+            .expect("XChaCha20-Poly1305 encryption of a 32-byte key cannot fail");
+
+        let mut wrapped = [0; WRAPPED_KEY_LENGTH];
+        wrapped[..32].copy_from_slice(ephemeral_public.as_bytes());
+        wrapped[32..32 + NONCE_LENGTH].copy_from_slice(&nonce);
+        wrapped[32 + NONCE_LENGTH..].copy_from_slice(&sealed);
+        Ok(wrapped)
+    }
+
+    /// Opens a `wrapped_key` from the reader's `EntryReader` account.
+    pub fn unwrap_with(reader: &Keypair, wrapped: &[u8; WRAPPED_KEY_LENGTH]) -> Result<Self, CryptoError> {
+        // An Ed25519 secret scalar is the clamped low half of SHA-512(seed).
+        let digest = Sha512::digest(&reader.to_bytes()[..32]);
+        let mut secret = [0; 32];
+        secret.copy_from_slice(&digest[..32]);
+
+        let mut ephemeral_public = [0; 32];
+        ephemeral_public.copy_from_slice(&wrapped[..32]);
+        let ephemeral_public = MontgomeryPoint(ephemeral_public);
+        let mut nonce = [0; NONCE_LENGTH];
+        nonce.copy_from_slice(&wrapped[32..32 + NONCE_LENGTH]);
+
+        let wrapping_key = wrapping_key(
+            &(clamp(secret) * ephemeral_public),
+            &ephemeral_public,
+            &reader.pubkey(),
+        )?;
+        let key = wrapping_key
+            .cipher()
+            .decrypt(&XNonce::from(nonce), &wrapped[32 + NONCE_LENGTH..])
+            .map_err(|_| CryptoError::Decryption)?;
+        Ok(Self {
+            key: key.try_into().map_err(|_| CryptoError::Decryption)?,
+            scheme: EncryptionScheme::XChaCha20Poly1305Envelope,
+        })
+    }
+
+    /// Encrypts `message` under a fresh random nonce.
+    pub fn encrypt(&self, message: &str) -> Result<EncryptedContent, CryptoError> {
+        if message.len() > MESSAGE_LENGTH {
+            return Err(CryptoError::MessageTooLong);
+        }
+        let mut nonce = [0; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&XNonce::from(nonce), message.as_bytes())
+            .expect("XChaCha20-Poly1305 encryption of a bounded message cannot fail");
+        Ok(EncryptedContent {
+            scheme: self.scheme,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    pub fn decrypt(&self, content: &EncryptedContent) -> Result<String, CryptoError> {
+        let plaintext = match content.scheme {
+            EncryptionScheme::XChaCha20Poly1305 | EncryptionScheme::XChaCha20Poly1305Envelope => self
+                .cipher()
+                .decrypt(&XNonce::from(content.nonce), content.ciphertext.as_ref())
+                .map_err(|_| CryptoError::Decryption)?,
+        };
+        String::from_utf8(plaintext).map_err(|_| CryptoError::InvalidUtf8)
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&Key::from(self.key))
+    }
+}
+
+fn clamp(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
+
+/// Derives the key that seals a content key from the Diffie-Hellman output,
+/// bound to both public keys of the exchange.
+fn wrapping_key(
+    shared: &MontgomeryPoint,
+    ephemeral_public: &MontgomeryPoint,
+    reader: &Pubkey,
+) -> Result<EntryKey, CryptoError> {
+    // A low-order reader or ephemeral point collapses the exchange to zero.
+    if shared.as_bytes() == &[0; 32] {
+        return Err(CryptoError::InvalidReaderKey);
+    }
+    let mut salt = [0; 64];
+    salt[..32].copy_from_slice(ephemeral_public.as_bytes());
+    salt[32..].copy_from_slice(reader.as_ref());
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), shared.as_bytes());
+    let mut key = [0; 32];
+    hkdf.expand(WRAP_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Ok(EntryKey::from_bytes(key))
+}
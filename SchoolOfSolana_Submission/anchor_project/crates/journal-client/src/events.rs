@@ -0,0 +1,129 @@
+//! Decoding `journal_dapp` events from transaction logs.
+//!
+//! `emit!` writes each event as a `Program data:` line holding the base64 of
+//! its discriminator followed by its Borsh encoding. Only lines logged while
+//! `journal_dapp` itself is executing are decoded, so events of programs it
+//! calls, or that call it, are never mistaken for journal events.
+
+use std::fmt;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use journal_dapp::events::*;
+
+#[derive(Debug)]
+pub enum EventError {
+    /// A `Program data:` line is not valid base64.
+    InvalidBase64,
+    /// The payload of a known event does not decode.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::InvalidBase64 => write!(f, "program data is not valid base64"),
+            EventError::Malformed(name) => write!(f, "malformed {name} event"),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+macro_rules! journal_events {
+    ($($name:ident),* $(,)?) => {
+        /// Any event emitted by `journal_dapp`.
+        pub enum JournalEvent {
+            $($name($name),)*
+        }
+
+        impl JournalEvent {
+            /// Decodes the bytes of one `Program data:` line. Data that is not
+            /// a known journal event yields `None`.
+            pub fn decode(data: &[u8]) -> Result<Option<Self>, EventError> {
+                if data.len() < 8 {
+                    return Ok(None);
+                }
+                let (discriminator, mut payload) = data.split_at(8);
+                $(
+                    if discriminator == $name::DISCRIMINATOR {
+                        return $name::deserialize(&mut payload)
+                            .map(|event| Some(Self::$name(event)))
+                            .map_err(|_| EventError::Malformed(stringify!($name)));
+                    }
+                )*
+                Ok(None)
+            }
+
+            /// Name of the event, as declared in the program.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+journal_events!(
+    EntryCreated,
+    EntryUpdated,
+    EntryDeleted,
+    EntryArchived,
+    EntryRestored,
+    EntryPurged,
+    EntrySealed,
+    EntryNotarized,
+    EntryRenamed,
+    VisibilitySet,
+    NotebookCreated,
+    NotebookRenamed,
+    NotebookClosed,
+    EntryMoved,
+    EntryMigrated,
+    EntryUpgraded,
+    HistoryModeSet,
+    RevisionRestored,
+    RevisionPruned,
+    PageAppended,
+    PageReplaced,
+    PageRemoved,
+    ReaderGranted,
+    ReaderRevoked,
+    DelegateSet,
+    DelegateRevoked,
+    TransferOffered,
+    TransferCancelled,
+    EntryTransferred,
+    JournalSwept,
+    JournalClosed,
+    JournalClosing,
+);
+
+/// Extracts the journal events from the log messages of one transaction, in
+/// the order they were emitted.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Result<Vec<JournalEvent>, EventError> {
+    let program_id = journal_dapp::ID.to_string();
+    let mut call_stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let Some(rest) = line.as_ref().strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if call_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let data = STANDARD.decode(data).map_err(|_| EventError::InvalidBase64)?;
+            events.extend(JournalEvent::decode(&data)?);
+        } else if let Some((program, status)) = rest.split_once(' ') {
+            if status.starts_with("invoke [") {
+                call_stack.push(program);
+            } else if status == "success" || status.starts_with("failed") {
+                call_stack.pop();
+            }
+        }
+    }
+    Ok(events)
+}
@@ -0,0 +1,444 @@
+//! Backing a journal up to files and bringing notes back in.
+//!
+//! An export is either a folder of Markdown files, one per entry with its
+//! metadata in YAML front-matter, or a single JSON archive. Importing replays
+//! an export through `create_entry` under the signing wallet and skips every
+//! entry whose title is already taken, so an interrupted import resumes by
+//! running it again. Markdown files without front-matter are imported too,
+//! titled after their file name. Notebook ids are per wallet, so an entry
+//! whose notebook does not exist in the target journal is created outside
+//! any notebook and reported.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use journal_dapp::states::Notebook;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::accounts::{all_entries_by_owner, fetch};
+use crate::compose::{self, ComposeError};
+use crate::connection::{ClientError, JournalConnection};
+use crate::pda;
+
+/// Version of the JSON archive layout.
+pub const ARCHIVE_VERSION: u64 = 1;
+
+#[derive(Debug)]
+pub enum ExportError {
+    /// A file or folder of the export could not be read or written.
+    Io { path: PathBuf, error: std::io::Error },
+    /// A file of the export does not parse.
+    Malformed { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            ExportError::Malformed { path, reason } => write!(f, "{}: {reason}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A folder with one Markdown file per entry.
+    Markdown,
+    /// A single JSON file.
+    Json,
+}
+
+/// An entry as it is written to or read from an export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedEntry {
+    pub title: String,
+    /// Full text, message and pages joined.
+    pub text: String,
+    pub tags: Vec<String>,
+    /// Id of the notebook holding the entry; 0 when it is in none.
+    pub notebook: u32,
+    /// Wallet the entry was exported from; `None` for notes written by hand.
+    pub owner: Option<Pubkey>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+/// A wallet's entries, oldest first.
+#[derive(Debug, Default)]
+pub struct Export {
+    pub entries: Vec<ExportedEntry>,
+    /// Encrypted and notarized entries, which have no plaintext to export.
+    pub skipped: Vec<Pubkey>,
+}
+
+/// What an import would do, computed without sending anything.
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    /// Entries to create, in order.
+    pub create: Vec<ExportedEntry>,
+    /// Entries whose title is taken in their notebook, by the journal or by
+    /// an earlier entry of the export.
+    pub duplicates: Vec<ExportedEntry>,
+    /// Titles of entries in `create` whose notebook does not exist in the
+    /// journal, with the notebook id they were exported under; they are
+    /// created outside any notebook instead.
+    pub unfiled: Vec<(String, u32)>,
+}
+
+/// Collects every entry of `owner`, archived ones included.
+pub fn export_entries(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+) -> Result<Export, ClientError> {
+    let mut journal_entries = all_entries_by_owner(connection, owner)?;
+    journal_entries.sort_by(|(_, a), (_, b)| (a.created_at, &a.title).cmp(&(b.created_at, &b.title)));
+
+    let mut export = Export::default();
+    for (address, journal_entry) in journal_entries {
+        if journal_entry.encrypted.is_some() || journal_entry.notarization.is_some() {
+            export.skipped.push(address);
+            continue;
+        }
+        export.entries.push(ExportedEntry {
+            text: compose::entry_text(connection, &journal_entry)?,
+            title: journal_entry.title,
+            tags: journal_entry.tags,
+            notebook: journal_entry.notebook,
+            owner: Some(journal_entry.owner),
+            created_at: Some(journal_entry.created_at),
+            updated_at: Some(journal_entry.updated_at),
+        });
+    }
+    Ok(export)
+}
+
+/// Splits `entries` into those `owner` can create and those that would
+/// collide with an existing entry or an earlier one of the export. Titles
+/// collide the way entry addresses do, so case does not matter. Entries of a
+/// notebook `owner` does not have are moved to notebook 0 before checking.
+pub fn plan_import(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Pubkey,
+    entries: Vec<ExportedEntry>,
+) -> Result<ImportPlan, ClientError> {
+    let mut taken: HashSet<Pubkey> = all_entries_by_owner(connection, owner)?
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    let mut notebooks = HashMap::from([(0, true)]);
+    let mut plan = ImportPlan::default();
+    for mut entry in entries {
+        let exported_notebook = entry.notebook;
+        let exists = match notebooks.get(&exported_notebook) {
+            Some(&exists) => exists,
+            None => {
+                let exists = fetch::<Notebook>(connection, &pda::notebook(owner, exported_notebook))?.is_some();
+                notebooks.insert(exported_notebook, exists);
+                exists
+            }
+        };
+        if !exists {
+            entry.notebook = 0;
+        }
+        if taken.insert(pda::entry(owner, &entry.title, entry.notebook)) {
+            if !exists {
+                plan.unfiled.push((entry.title.clone(), exported_notebook));
+            }
+            plan.create.push(entry);
+        } else {
+            plan.duplicates.push(entry);
+        }
+    }
+    Ok(plan)
+}
+
+/// Creates the entries of `plan`, calling `on_created` after each one.
+pub fn import_entries(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Keypair,
+    plan: &ImportPlan,
+    mut on_created: impl FnMut(&ExportedEntry),
+) -> Result<(), ComposeError> {
+    for entry in &plan.create {
+        compose::create_entry(connection, owner, entry.notebook, &entry.title, &entry.text, &entry.tags)?;
+        on_created(entry);
+    }
+    Ok(())
+}
+
+/// Writes `entries` to `path`: a folder for Markdown, created if missing,
+/// or a file for JSON. Files already there with the same names are
+/// overwritten.
+pub fn write_export(path: &Path, format: Format, entries: &[ExportedEntry]) -> Result<(), ExportError> {
+    match format {
+        Format::Json => std::fs::write(path, to_json(entries)).map_err(io_error(path)),
+        Format::Markdown => {
+            std::fs::create_dir_all(path).map_err(io_error(path))?;
+            let mut names = HashSet::new();
+            for entry in entries {
+                let file = path.join(file_name(entry, &mut names));
+                std::fs::write(&file, to_markdown(entry)).map_err(io_error(&file))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Reads an export written by `write_export`: the `.md` files of a folder in
+/// file name order, or a JSON archive.
+pub fn read_export(path: &Path) -> Result<Vec<ExportedEntry>, ExportError> {
+    if !path.is_dir() {
+        let contents = std::fs::read_to_string(path).map_err(io_error(path))?;
+        return from_json(&contents).map_err(malformed(path));
+    }
+
+    let mut files = Vec::new();
+    for dir_entry in std::fs::read_dir(path).map_err(io_error(path))? {
+        let file = dir_entry.map_err(io_error(path))?.path();
+        if file.is_file() && file.extension().is_some_and(|extension| extension == "md") {
+            files.push(file);
+        }
+    }
+    files.sort();
+    files
+        .iter()
+        .map(|file| {
+            let contents = std::fs::read_to_string(file).map_err(io_error(file))?;
+            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+            from_markdown(&stem, &contents).map_err(malformed(file))
+        })
+        .collect()
+}
+
+/// `entry` as a Markdown document with YAML front-matter.
+pub fn to_markdown(entry: &ExportedEntry) -> String {
+    let mut front_matter = format!("title: {}\n", json!(entry.title));
+    if let Some(owner) = entry.owner {
+        front_matter.push_str(&format!("owner: {owner}\n"));
+    }
+    if let Some(created_at) = entry.created_at {
+        front_matter.push_str(&format!("created_at: {}\n", rfc3339(created_at)));
+    }
+    if let Some(updated_at) = entry.updated_at {
+        front_matter.push_str(&format!("updated_at: {}\n", rfc3339(updated_at)));
+    }
+    if !entry.tags.is_empty() {
+        front_matter.push_str(&format!("tags: {}\n", json!(entry.tags)));
+    }
+    if entry.notebook != 0 {
+        front_matter.push_str(&format!("notebook: {}\n", entry.notebook));
+    }
+    format!("---\n{front_matter}---\n\n{}\n", entry.text)
+}
+
+/// Parses a Markdown document. Without front-matter the whole document is
+/// the text and `file_stem` the title.
+pub fn from_markdown(file_stem: &str, contents: &str) -> Result<ExportedEntry, String> {
+    let contents = contents.replace("\r\n", "\n");
+    let mut entry = ExportedEntry {
+        title: file_stem.to_string(),
+        text: contents.clone(),
+        tags: Vec::new(),
+        notebook: 0,
+        owner: None,
+        created_at: None,
+        updated_at: None,
+    };
+    let Some((front_matter, body)) = split_front_matter(&contents) else {
+        return Ok(entry);
+    };
+    let body = body.strip_prefix('\n').unwrap_or(body);
+    entry.text = body.strip_suffix('\n').unwrap_or(body).to_string();
+
+    for line in front_matter.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "title" => entry.title = unquote(value)?,
+            "owner" => entry.owner = Some(Pubkey::from_str(value).map_err(|_| format!("invalid owner: {value}"))?),
+            "created_at" => entry.created_at = Some(parse_rfc3339(value)?),
+            "updated_at" => entry.updated_at = Some(parse_rfc3339(value)?),
+            "tags" => {
+                let list = value.strip_prefix('[').and_then(|list| list.strip_suffix(']'));
+                let list = list.ok_or_else(|| format!("tags must be a [list]: {value}"))?;
+                entry.tags = list
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(unquote)
+                    .collect::<Result<_, _>>()?;
+            }
+            "notebook" => entry.notebook = value.parse().map_err(|_| format!("invalid notebook: {value}"))?,
+            _ => {}
+        }
+    }
+    Ok(entry)
+}
+
+/// `entries` as a JSON archive.
+pub fn to_json(entries: &[ExportedEntry]) -> String {
+    let entries: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "title": entry.title,
+                "owner": entry.owner.map(|owner| owner.to_string()),
+                "created_at": entry.created_at.map(rfc3339),
+                "updated_at": entry.updated_at.map(rfc3339),
+                "tags": entry.tags,
+                "notebook": entry.notebook,
+                "text": entry.text,
+            })
+        })
+        .collect();
+    format!("{:#}\n", json!({ "version": ARCHIVE_VERSION, "entries": entries }))
+}
+
+/// Parses a JSON archive.
+pub fn from_json(contents: &str) -> Result<Vec<ExportedEntry>, String> {
+    let archive: Value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+    let version = archive["version"].as_u64().ok_or("not a journal archive")?;
+    if version > ARCHIVE_VERSION {
+        return Err(format!("archive version {version} is newer than this client"));
+    }
+    let entries = archive["entries"].as_array().ok_or("not a journal archive")?;
+    entries
+        .iter()
+        .map(|item| {
+            let string = |key: &str| item[key].as_str();
+            let title = string("title").ok_or("entry without a title")?;
+            let tags = match &item["tags"] {
+                Value::Null => Vec::new(),
+                tags => serde_json::from_value(tags.clone()).map_err(|_| format!("{title}: invalid tags"))?,
+            };
+            let notebook = match &item["notebook"] {
+                Value::Null => 0,
+                notebook => notebook
+                    .as_u64()
+                    .and_then(|notebook| u32::try_from(notebook).ok())
+                    .ok_or_else(|| format!("{title}: invalid notebook"))?,
+            };
+            Ok(ExportedEntry {
+                title: title.to_string(),
+                text: string("text").unwrap_or_default().to_string(),
+                tags,
+                notebook,
+                owner: string("owner")
+                    .map(|owner| Pubkey::from_str(owner).map_err(|_| format!("{title}: invalid owner")))
+                    .transpose()?,
+                created_at: string("created_at").map(parse_rfc3339).transpose()?,
+                updated_at: string("updated_at").map(parse_rfc3339).transpose()?,
+            })
+        })
+        .collect()
+}
+
+/// `unix_timestamp` as an RFC 3339 time in UTC, such as
+/// `2024-05-01T08:30:00Z`.
+pub fn rfc3339(unix_timestamp: i64) -> String {
+    let (days, seconds) = (unix_timestamp.div_euclid(86_400), unix_timestamp.rem_euclid(86_400));
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Parses the times written by `rfc3339`.
+pub fn parse_rfc3339(time: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid time: {time}, expected YYYY-MM-DDTHH:MM:SSZ");
+    let bytes = time.as_bytes();
+    let separators = [(4, b'-'), (7, b'-'), (10, b'T'), (13, b':'), (16, b':'), (19, b'Z')];
+    if bytes.len() != 20 || separators.iter().any(|&(i, separator)| bytes[i] != separator) {
+        return Err(invalid());
+    }
+    let field = |range: std::ops::Range<usize>| time[range].parse::<i64>().map_err(|_| invalid());
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+    // Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Ok(days * 86_400 + hour * 3600 + minute * 60 + second)
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> ExportError {
+    let path = path.to_path_buf();
+    move |error| ExportError::Io { path, error }
+}
+
+fn malformed(path: &Path) -> impl FnOnce(String) -> ExportError {
+    let path = path.to_path_buf();
+    move |reason| ExportError::Malformed { path, reason }
+}
+
+/// Front-matter and body of a document that opens with a `---` line.
+fn split_front_matter(contents: &str) -> Option<(&str, &str)> {
+    let rest = contents.strip_prefix("---\n")?;
+    if let Some(body) = rest.strip_prefix("---\n") {
+        return Some(("", body));
+    }
+    let end = rest.find("\n---\n")?;
+    Some((&rest[..end + 1], &rest[end + 5..]))
+}
+
+/// A YAML scalar: double-quoted with JSON escapes, single-quoted, or plain.
+fn unquote(value: &str) -> Result<String, String> {
+    if value.starts_with('"') {
+        serde_json::from_str(value).map_err(|_| format!("invalid quoted string: {value}"))
+    } else if let Some(inner) = value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        Ok(inner.replace("''", "'"))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+/// `<date>-<title>.md`, made unique among `taken`.
+fn file_name(entry: &ExportedEntry, taken: &mut HashSet<String>) -> String {
+    let mut slug = String::new();
+    for c in entry.title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let slug = if slug.is_empty() { "entry" } else { slug };
+    let stem = match entry.created_at {
+        Some(created_at) => format!("{}-{slug}", &rfc3339(created_at)[..10]),
+        None => slug.to_string(),
+    };
+    let mut name = format!("{stem}.md");
+    let mut copy = 1;
+    while !taken.insert(name.clone()) {
+        copy += 1;
+        name = format!("{stem}-{copy}.md");
+    }
+    name
+}
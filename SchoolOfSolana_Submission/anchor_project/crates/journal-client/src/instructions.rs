@@ -0,0 +1,794 @@
+//! Typed builders for every `journal_dapp` instruction.
+//!
+//! Builders only derive addresses; they never fetch. Instructions that act on
+//! an existing entry take its decoded `JournalEntry`, and the few inputs that
+//! live in other accounts are passed explicitly: the index page of an entry
+//! (see `accounts::find_entry_page`), the owner's `UserJournal::current_page`
+//! for new entries, and the `EntryReader` and `JournalRevision` accounts
+//! of an entry that is closed (see `accounts::entry_readers` and
+//! `accounts::entry_revisions`). Remaining accounts are appended in
+//! the order the program expects.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use journal_dapp::states::*;
+use journal_dapp::{accounts, instruction};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData, remaining: Vec<AccountMeta>) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction {
+        program_id: journal_dapp::ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+fn writable(addresses: impl IntoIterator<Item = Pubkey>) -> Vec<AccountMeta> {
+    addresses.into_iter().map(|address| AccountMeta::new(address, false)).collect()
+}
+
+/// The owner's `TagIndex` of each of the entry's tags, in its order.
+fn tag_indexes(owner: &Pubkey, journal_entry: &JournalEntry) -> Vec<AccountMeta> {
+    writable(journal_entry.tags.iter().map(|tag| pda::tag_index(owner, tag)))
+}
+
+fn notebook_address(owner: &Pubkey, notebook: u32) -> Option<Pubkey> {
+    (notebook != 0).then(|| pda::notebook(owner, notebook))
+}
+
+/// The accounts `delete_entry`, `purge_entry` and `close_all_entries` close
+/// along with an entry: its pages, then `readers`, then the tag indexes it
+/// is listed on, then `revisions`.
+pub fn entry_children(journal_entry: &JournalEntry, readers: &[Pubkey], revisions: &[Pubkey]) -> Vec<Pubkey> {
+    let entry = pda::entry_of(journal_entry);
+    let mut children: Vec<Pubkey> = journal_entry
+        .pages
+        .iter()
+        .map(|page_id| pda::entry_page(&entry, *page_id))
+        .collect();
+    children.extend_from_slice(readers);
+    children.extend(journal_entry.tags.iter().map(|tag| pda::tag_index(&journal_entry.owner, tag)));
+    children.extend_from_slice(revisions);
+    children
+}
+
+/// The accounts `rename_entry` and `move_entry` carry over to `new_entry`
+/// after the entry's tag indexes: the old and the new address of each page,
+/// then of the grant of each of `readers`, then of each stored revision in
+/// `revisions`. `readers` are the wallets holding grants, not the grants.
+fn moved_children(
+    journal_entry: &JournalEntry,
+    new_entry: &Pubkey,
+    readers: &[Pubkey],
+    revisions: &[u32],
+) -> Vec<Pubkey> {
+    let entry = pda::entry_of(journal_entry);
+    let mut children = Vec::new();
+    for page_id in &journal_entry.pages {
+        children.extend([pda::entry_page(&entry, *page_id), pda::entry_page(new_entry, *page_id)]);
+    }
+    for reader in readers {
+        children.extend([pda::entry_reader(&entry, reader), pda::entry_reader(new_entry, reader)]);
+    }
+    for revision in revisions {
+        children.extend([pda::revision(&entry, *revision), pda::revision(new_entry, *revision)]);
+    }
+    children
+}
+
+/// `page_index` is the owner's `UserJournal::current_page`, 0 for a new
+/// journal; `notebook` is 0 for an entry outside any notebook.
+pub fn create_entry(
+    owner: &Pubkey,
+    page_index: u64,
+    notebook: u32,
+    title: &str,
+    message: &str,
+    tags: &[String],
+) -> Instruction {
+    let accounts = accounts::CreateEntry {
+        journal_entry: pda::entry(owner, title, notebook),
+        user_journal: pda::user_journal(owner),
+        journal_page: pda::journal_page(owner, page_index),
+        notebook: notebook_address(owner, notebook),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CreateEntry {
+        title: title.to_string(),
+        message: message.to_string(),
+        tags: tags.to_vec(),
+    };
+    build(accounts, data, writable(tags.iter().map(|tag| pda::tag_index(owner, tag))))
+}
+
+/// Replaces the message and, when `tags` is set, the tags of an entry. The
+/// `authority` is the owner or one of their delegates.
+pub fn update_entry(
+    authority: &Pubkey,
+    journal_entry: &JournalEntry,
+    message: &str,
+    tags: Option<&[String]>,
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::UpdateEntry {
+        journal_entry: entry,
+        journal_revision: next_revision(&entry, journal_entry),
+        journal_delegate: (*authority != owner).then(|| pda::delegate(&owner, authority)),
+        owner,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    let mut tag_indexes = Vec::new();
+    if let Some(tags) = tags {
+        let old_tags = &journal_entry.tags;
+        let removed = old_tags.iter().filter(|tag| !tags.contains(tag));
+        let added = tags.iter().filter(|tag| !old_tags.contains(tag));
+        tag_indexes = writable(removed.chain(added).map(|tag| pda::tag_index(&owner, tag)));
+    }
+    let data = instruction::UpdateEntry {
+        _title: journal_entry.title.clone(),
+        message: message.to_string(),
+        tags: tags.map(<[String]>::to_vec),
+    };
+    build(accounts, data, tag_indexes)
+}
+
+/// The revision account a plaintext edit snapshots into, set only in
+/// history mode.
+fn next_revision(entry: &Pubkey, journal_entry: &JournalEntry) -> Option<Pubkey> {
+    journal_entry
+        .history_enabled
+        .then(|| pda::revision(entry, journal_entry.revision))
+}
+
+pub fn seal_entry(journal_entry: &JournalEntry) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::SealEntry {
+        journal_entry: entry,
+        owner: journal_entry.owner,
+    };
+    let data = instruction::SealEntry {
+        _title: journal_entry.title.clone(),
+    };
+    let pages = journal_entry
+        .pages
+        .iter()
+        .map(|page_id| AccountMeta::new_readonly(pda::entry_page(&entry, *page_id), false))
+        .collect();
+    build(accounts, data, pages)
+}
+
+pub fn notarize(
+    owner: &Pubkey,
+    page_index: u64,
+    notebook: u32,
+    title: &str,
+    sha256: [u8; 32],
+    uri: Option<&str>,
+    mime: Option<&str>,
+) -> Instruction {
+    let accounts = accounts::Notarize {
+        journal_entry: pda::entry(owner, title, notebook),
+        user_journal: pda::user_journal(owner),
+        journal_page: pda::journal_page(owner, page_index),
+        notebook: notebook_address(owner, notebook),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::Notarize {
+        title: title.to_string(),
+        sha256,
+        uri: uri.map(str::to_string),
+        mime: mime.map(str::to_string),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn append_to_entry(authority: &Pubkey, journal_entry: &JournalEntry, text: &str) -> Instruction {
+    let owner = journal_entry.owner;
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::AppendToEntry {
+        journal_entry: entry,
+        journal_revision: next_revision(&entry, journal_entry),
+        journal_delegate: (*authority != owner).then(|| pda::delegate(&owner, authority)),
+        owner,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    let data = instruction::AppendToEntry {
+        _title: journal_entry.title.clone(),
+        text: text.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn patch_entry(
+    authority: &Pubkey,
+    journal_entry: &JournalEntry,
+    offset: u32,
+    len: u32,
+    replacement: &str,
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::PatchEntry {
+        journal_entry: entry,
+        journal_revision: next_revision(&entry, journal_entry),
+        journal_delegate: (*authority != owner).then(|| pda::delegate(&owner, authority)),
+        owner,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    let data = instruction::PatchEntry {
+        _title: journal_entry.title.clone(),
+        offset,
+        len,
+        replacement: replacement.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+/// `next_entry_id` is the owner's `UserJournal::next_entry_id`, 0 for a new
+/// journal. Batch entries are created outside any notebook.
+pub fn create_entries(owner: &Pubkey, next_entry_id: u64, entries: &[NewEntry]) -> Instruction {
+    let accounts = accounts::CreateEntries {
+        user_journal: pda::user_journal(owner),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let first_page = next_entry_id / ENTRIES_PER_PAGE as u64;
+    let last_page = (next_entry_id + entries.len().max(1) as u64 - 1) / ENTRIES_PER_PAGE as u64;
+    let mut remaining = writable(entries.iter().map(|entry| pda::entry(owner, &entry.title, 0)));
+    remaining.extend(writable((first_page..=last_page).map(|index| pda::journal_page(owner, index))));
+    let data = instruction::CreateEntries {
+        entries: entries.to_vec(),
+    };
+    build(accounts, data, remaining)
+}
+
+/// Deletes each entry listed with the index of the page that holds it.
+pub fn delete_entries(owner: &Pubkey, entries: &[(&JournalEntry, u64)]) -> Instruction {
+    let accounts = accounts::DeleteEntries {
+        user_journal: pda::user_journal(owner),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let mut indexes: Vec<Pubkey> = Vec::new();
+    let containers = entries.iter().flat_map(|(journal_entry, page_index)| {
+        let tag_indexes = journal_entry.tags.iter().map(|tag| Some(pda::tag_index(owner, tag)));
+        [
+            Some(pda::journal_page(owner, *page_index)),
+            notebook_address(owner, journal_entry.notebook),
+        ]
+        .into_iter()
+        .chain(tag_indexes)
+    });
+    for address in containers.flatten() {
+        if !indexes.contains(&address) {
+            indexes.push(address);
+        }
+    }
+    let addresses: Vec<Pubkey> = entries.iter().map(|(journal_entry, _)| pda::entry_of(journal_entry)).collect();
+    let mut remaining = writable(addresses.iter().copied());
+    remaining.extend(writable(addresses.iter().map(pda::transfer_offer)));
+    remaining.extend(writable(indexes));
+    let data = instruction::DeleteEntries {
+        titles: entries.iter().map(|(journal_entry, _)| journal_entry.title.clone()).collect(),
+    };
+    build(accounts, data, remaining)
+}
+
+/// `readers` and `revisions` are the entry's `EntryReader` and
+/// `JournalRevision` accounts, all of which are closed.
+pub fn delete_entry(
+    authority: &Pubkey,
+    journal_entry: &JournalEntry,
+    page_index: u64,
+    readers: &[Pubkey],
+    revisions: &[Pubkey],
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let accounts = accounts::DeleteEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        user_journal: pda::user_journal(&owner),
+        transfer_offer: pda::transfer_offer(&pda::entry_of(journal_entry)),
+        journal_page: pda::journal_page(&owner, page_index),
+        notebook: notebook_address(&owner, journal_entry.notebook),
+        journal_delegate: (*authority != owner).then(|| pda::delegate(&owner, authority)),
+        owner,
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    let data = instruction::DeleteEntry {
+        _title: journal_entry.title.clone(),
+    };
+    build(accounts, data, writable(entry_children(journal_entry, readers, revisions)))
+}
+
+pub fn archive_entry(journal_entry: &JournalEntry, grace_period: Option<u32>) -> Instruction {
+    let accounts = accounts::ArchiveEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::ArchiveEntry {
+        _title: journal_entry.title.clone(),
+        grace_period,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn restore_entry(journal_entry: &JournalEntry) -> Instruction {
+    let accounts = accounts::RestoreEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::RestoreEntry {
+        _title: journal_entry.title.clone(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+/// `readers` and `revisions` are the entry's `EntryReader` and
+/// `JournalRevision` accounts, all of which are closed.
+pub fn purge_entry(
+    journal_entry: &JournalEntry,
+    page_index: u64,
+    readers: &[Pubkey],
+    revisions: &[Pubkey],
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let accounts = accounts::PurgeEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        user_journal: pda::user_journal(&owner),
+        transfer_offer: pda::transfer_offer(&pda::entry_of(journal_entry)),
+        journal_page: pda::journal_page(&owner, page_index),
+        notebook: notebook_address(&owner, journal_entry.notebook),
+        owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::PurgeEntry {
+        _title: journal_entry.title.clone(),
+    };
+    build(accounts, data, writable(entry_children(journal_entry, readers, revisions)))
+}
+
+/// `feed_page` is the entry's `feed_page` when it is public, and the
+/// `PublicFeed::current_page` otherwise.
+pub fn set_visibility(journal_entry: &JournalEntry, feed_page: u64, visibility: Visibility) -> Instruction {
+    let accounts = accounts::SetVisibility {
+        journal_entry: pda::entry_of(journal_entry),
+        public_feed: pda::public_feed(),
+        feed_page: pda::feed_page(feed_page),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::SetVisibility {
+        _title: journal_entry.title.clone(),
+        visibility,
+    };
+    build(accounts, data, Vec::new())
+}
+
+/// `notebook_id` is one past the owner's `UserJournal::last_notebook_id`.
+pub fn create_notebook(owner: &Pubkey, notebook_id: u32, name: &str, description: &str) -> Instruction {
+    let accounts = accounts::CreateNotebook {
+        user_journal: pda::user_journal(owner),
+        notebook: pda::notebook(owner, notebook_id),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CreateNotebook {
+        name: name.to_string(),
+        description: description.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn rename_notebook(owner: &Pubkey, notebook_id: u32, name: &str) -> Instruction {
+    let accounts = accounts::RenameNotebook {
+        notebook: pda::notebook(owner, notebook_id),
+        owner: *owner,
+    };
+    let data = instruction::RenameNotebook {
+        _notebook_id: notebook_id,
+        name: name.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn close_notebook(owner: &Pubkey, notebook_id: u32) -> Instruction {
+    let accounts = accounts::CloseNotebook {
+        notebook: pda::notebook(owner, notebook_id),
+        owner: *owner,
+    };
+    let data = instruction::CloseNotebook {
+        _notebook_id: notebook_id,
+    };
+    build(accounts, data, Vec::new())
+}
+
+/// Moves an entry into notebook `notebook_id`, 0 taking it out of any.
+/// `readers` and `revisions` are as for `rename_entry`.
+pub fn move_entry(
+    journal_entry: &JournalEntry,
+    page_index: u64,
+    notebook_id: u32,
+    readers: &[Pubkey],
+    revisions: &[u32],
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let new_entry = pda::entry(&owner, &journal_entry.title, notebook_id);
+    let accounts = accounts::MoveEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        new_entry,
+        transfer_offer: pda::transfer_offer(&pda::entry_of(journal_entry)),
+        from_notebook: notebook_address(&owner, journal_entry.notebook),
+        to_notebook: notebook_address(&owner, notebook_id),
+        journal_page: pda::journal_page(&owner, page_index),
+        owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::MoveEntry {
+        title: journal_entry.title.clone(),
+        notebook_id,
+    };
+    let mut remaining = tag_indexes(&owner, journal_entry);
+    remaining.extend(writable(moved_children(journal_entry, &new_entry, readers, revisions)));
+    build(accounts, data, remaining)
+}
+
+/// Renames an entry, moving its pages, the grants of `readers` and the
+/// stored `revisions` along and closing an open transfer offer. `readers`
+/// are the wallets of `entry_readers` and `revisions` the numbers of
+/// `entry_revisions`; both must be complete.
+pub fn rename_entry(
+    journal_entry: &JournalEntry,
+    page_index: u64,
+    new_title: &str,
+    readers: &[Pubkey],
+    revisions: &[u32],
+) -> Instruction {
+    let owner = journal_entry.owner;
+    let new_entry = pda::entry(&owner, new_title, journal_entry.notebook);
+    let accounts = accounts::RenameEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        new_entry,
+        transfer_offer: pda::transfer_offer(&pda::entry_of(journal_entry)),
+        journal_page: pda::journal_page(&owner, page_index),
+        owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::RenameEntry {
+        _old_title: journal_entry.title.clone(),
+        new_title: new_title.to_string(),
+    };
+    let mut remaining = tag_indexes(&owner, journal_entry);
+    remaining.extend(writable(moved_children(journal_entry, &new_entry, readers, revisions)));
+    build(accounts, data, remaining)
+}
+
+/// `page_index` is the owner's `UserJournal::current_page`.
+pub fn migrate_entry(owner: &Pubkey, page_index: u64, title: &str) -> Instruction {
+    let accounts = accounts::MigrateEntry {
+        legacy_entry: pda::legacy_entry(owner, title),
+        journal_entry: pda::entry(owner, title, 0),
+        user_journal: pda::user_journal(owner),
+        journal_page: pda::journal_page(owner, page_index),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::MigrateEntry {
+        title: title.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn upgrade_entry(owner: &Pubkey, title: &str) -> Instruction {
+    let accounts = accounts::UpgradeEntry {
+        journal_entry: pda::entry(owner, title, 0),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::UpgradeEntry {
+        _title: title.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn set_history_mode(journal_entry: &JournalEntry, enabled: bool) -> Instruction {
+    let accounts = accounts::SetHistoryMode {
+        journal_entry: pda::entry_of(journal_entry),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::SetHistoryMode {
+        _title: journal_entry.title.clone(),
+        enabled,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn read_revision(journal_entry: &JournalEntry, revision: u32) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::ReadRevision {
+        journal_entry: entry,
+        journal_revision: pda::revision(&entry, revision),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::ReadRevision {
+        _title: journal_entry.title.clone(),
+        _revision: revision,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn restore_revision(journal_entry: &JournalEntry, revision: u32) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::RestoreRevision {
+        journal_entry: entry,
+        restored_revision: pda::revision(&entry, revision),
+        journal_revision: next_revision(&entry, journal_entry),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::RestoreRevision {
+        _title: journal_entry.title.clone(),
+        _revision: revision,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn prune_revision(journal_entry: &JournalEntry, revision: u32) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::PruneRevision {
+        journal_entry: entry,
+        journal_revision: pda::revision(&entry, revision),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::PruneRevision {
+        _title: journal_entry.title.clone(),
+        _revision: revision,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn append_page(journal_entry: &JournalEntry, content: &str) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::AppendPage {
+        journal_entry: entry,
+        entry_page: pda::entry_page(&entry, journal_entry.next_page_id),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::AppendPage {
+        _title: journal_entry.title.clone(),
+        content: content.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn replace_page(journal_entry: &JournalEntry, page_id: u16, content: &str) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::ReplacePage {
+        journal_entry: entry,
+        entry_page: pda::entry_page(&entry, page_id),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::ReplacePage {
+        _title: journal_entry.title.clone(),
+        _page_id: page_id,
+        content: content.to_string(),
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn remove_page(journal_entry: &JournalEntry, page_id: u16) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::RemovePage {
+        journal_entry: entry,
+        entry_page: pda::entry_page(&entry, page_id),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::RemovePage {
+        _title: journal_entry.title.clone(),
+        page_id,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn create_encrypted_entry(
+    owner: &Pubkey,
+    page_index: u64,
+    notebook: u32,
+    title: &str,
+    content: EncryptedContent,
+) -> Instruction {
+    let accounts = accounts::CreateEncryptedEntry {
+        journal_entry: pda::entry(owner, title, notebook),
+        user_journal: pda::user_journal(owner),
+        journal_page: pda::journal_page(owner, page_index),
+        notebook: notebook_address(owner, notebook),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CreateEncryptedEntry {
+        title: title.to_string(),
+        content,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn update_encrypted_entry(journal_entry: &JournalEntry, content: EncryptedContent) -> Instruction {
+    let accounts = accounts::UpdateEncryptedEntry {
+        journal_entry: pda::entry_of(journal_entry),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::UpdateEncryptedEntry {
+        _title: journal_entry.title.clone(),
+        content,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn grant_reader(
+    journal_entry: &JournalEntry,
+    reader: &Pubkey,
+    wrapped_key: [u8; WRAPPED_KEY_LENGTH],
+) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::GrantReader {
+        journal_entry: entry,
+        entry_reader: pda::entry_reader(&entry, reader),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::GrantReader {
+        _title: journal_entry.title.clone(),
+        reader: *reader,
+        wrapped_key,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn revoke_reader(journal_entry: &JournalEntry, reader: &Pubkey) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::RevokeReader {
+        journal_entry: entry,
+        entry_reader: pda::entry_reader(&entry, reader),
+        owner: journal_entry.owner,
+    };
+    let data = instruction::RevokeReader {
+        _title: journal_entry.title.clone(),
+        _reader: *reader,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn set_delegate(owner: &Pubkey, delegate: &Pubkey, permissions: u8, expires_at: Option<i64>) -> Instruction {
+    let accounts = accounts::SetDelegate {
+        journal_delegate: pda::delegate(owner, delegate),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::SetDelegate {
+        delegate: *delegate,
+        permissions,
+        expires_at,
+    };
+    build(accounts, data, Vec::new())
+}
+
+pub fn revoke_delegate(owner: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let accounts = accounts::RevokeDelegate {
+        journal_delegate: pda::delegate(owner, delegate),
+        owner: *owner,
+    };
+    let data = instruction::RevokeDelegate { _delegate: *delegate };
+    build(accounts, data, Vec::new())
+}
+
+/// `accounts` holds, for each of the `entry_count` entries, the entry and its
+/// `entry_children`, followed by the distinct notebooks of those entries.
+pub fn close_all_entries(owner: &Pubkey, page_index: u64, entry_count: u8, accounts: &[Pubkey]) -> Instruction {
+    let fixed = accounts::CloseAllEntries {
+        user_journal: pda::user_journal(owner),
+        journal_page: pda::journal_page(owner, page_index),
+        owner: *owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CloseAllEntries {
+        _page_index: page_index,
+        entry_count,
+    };
+    build(fixed, data, writable(accounts.iter().copied()))
+}
+
+/// The next `close_journal` call, carrying at most `max_accounts` of the
+/// index pages and notebooks left to close. The current page is only passed
+/// in the call that closes the journal, which is the one where everything
+/// left fits.
+pub fn close_journal(owner: &Pubkey, user_journal: &UserJournal, max_accounts: usize) -> Instruction {
+    let accounts = accounts::CloseJournal {
+        user_journal: pda::user_journal(owner),
+        owner: *owner,
+    };
+    let max_accounts = max_accounts.min(u8::MAX as usize);
+    let mut pages: Vec<u64> = (user_journal.closed_pages..user_journal.page_count()).collect();
+    let mut notebooks: Vec<u32> = (user_journal.closed_notebooks + 1..=user_journal.last_notebook_id).collect();
+    if pages.len() + notebooks.len() > max_accounts {
+        pages.retain(|index| *index != user_journal.current_page());
+        pages.truncate(max_accounts);
+        notebooks.truncate(max_accounts - pages.len());
+    }
+    let data = instruction::CloseJournal {
+        page_count: pages.len() as u8,
+    };
+    let pages = pages.into_iter().map(|index| pda::journal_page(owner, index));
+    let notebooks = notebooks.into_iter().map(|id| pda::notebook(owner, id));
+    build(accounts, data, writable(pages.chain(notebooks)))
+}
+
+pub fn offer_transfer(journal_entry: &JournalEntry, recipient: &Pubkey) -> Instruction {
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::OfferTransfer {
+        journal_entry: entry,
+        transfer_offer: pda::transfer_offer(&entry),
+        owner: journal_entry.owner,
+        system_program: system_program::ID,
+    };
+    let data = instruction::OfferTransfer {
+        _title: journal_entry.title.clone(),
+        recipient: *recipient,
+    };
+    build(accounts, data, Vec::new())
+}
+
+/// `previous_page` is the index page holding the entry in the previous
+/// owner's journal and `page_index` the recipient's
+/// `UserJournal::current_page`. The entry leaves its notebook.
+pub fn accept_transfer(
+    recipient: &Pubkey,
+    journal_entry: &JournalEntry,
+    previous_page: u64,
+    page_index: u64,
+) -> Instruction {
+    let previous_owner = journal_entry.owner;
+    let entry = pda::entry_of(journal_entry);
+    let accounts = accounts::AcceptTransfer {
+        journal_entry: entry,
+        transfer_offer: pda::transfer_offer(&entry),
+        new_entry: pda::entry(recipient, &journal_entry.title, 0),
+        previous_journal: pda::user_journal(&previous_owner),
+        previous_page: pda::journal_page(&previous_owner, previous_page),
+        notebook: notebook_address(&previous_owner, journal_entry.notebook),
+        user_journal: pda::user_journal(recipient),
+        journal_page: pda::journal_page(recipient, page_index),
+        previous_owner,
+        recipient: *recipient,
+        system_program: system_program::ID,
+    };
+    let data = instruction::AcceptTransfer {
+        title: journal_entry.title.clone(),
+    };
+    let mut remaining = tag_indexes(&previous_owner, journal_entry);
+    remaining.extend(tag_indexes(recipient, journal_entry));
+    build(accounts, data, remaining)
+}
+
+pub fn cancel_transfer(owner: &Pubkey, entry: &Pubkey) -> Instruction {
+    let accounts = accounts::CancelTransfer {
+        transfer_offer: pda::transfer_offer(entry),
+        owner: *owner,
+    };
+    let data = instruction::CancelTransfer { _entry: *entry };
+    build(accounts, data, Vec::new())
+}
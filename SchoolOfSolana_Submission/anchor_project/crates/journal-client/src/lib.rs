@@ -0,0 +1,19 @@
+//! Rust client for the `journal_dapp` program: PDA derivation, instruction
+//! builders and account decoding over any `JournalConnection`, plus the
+//! helpers built on them.
+
+pub mod accounts;
+pub mod compose;
+pub mod connection;
+pub mod crypto;
+pub mod events;
+pub mod export;
+pub mod instructions;
+pub mod notary;
+pub mod pages;
+pub mod pda;
+pub mod sweep;
+#[cfg(test)]
+mod vm;
+
+mod tests;
@@ -0,0 +1,107 @@
+//! Checking local documents against notarized entries.
+//!
+//! `notarize` stores only the SHA-256 of a document, and seals the entry in
+//! the same transaction. A document matches when its hash equals the stored
+//! one; the seal tells when that hash was recorded.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use journal_dapp::states::JournalEntry;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts;
+use crate::connection::{ClientError, JournalConnection};
+
+#[derive(Debug)]
+pub enum NotaryError {
+    /// The local file could not be read.
+    Io(io::Error),
+    /// The entry account could not be fetched.
+    Rpc(String),
+    /// The account is not a `JournalEntry` of this program.
+    NotAnEntry,
+    /// The entry holds a message rather than a document hash.
+    NotNotarized,
+}
+
+impl fmt::Display for NotaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotaryError::Io(err) => write!(f, "could not read the document: {err}"),
+            NotaryError::Rpc(reason) => write!(f, "could not fetch the entry: {reason}"),
+            NotaryError::NotAnEntry => write!(f, "account is not a journal entry"),
+            NotaryError::NotNotarized => write!(f, "entry is not a notarized document"),
+        }
+    }
+}
+
+impl std::error::Error for NotaryError {}
+
+impl From<io::Error> for NotaryError {
+    fn from(err: io::Error) -> Self {
+        NotaryError::Io(err)
+    }
+}
+
+/// Outcome of checking a document against a notarized entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub matches: bool,
+    pub document_hash: [u8; 32],
+    pub recorded_hash: [u8; 32],
+    /// Slot and Unix time at which the hash was recorded.
+    pub slot: u64,
+    pub recorded_at: i64,
+    pub uri: Option<String>,
+    pub mime: Option<String>,
+}
+
+/// SHA-256 of the file at `path`, read in chunks so large documents don't
+/// have to fit in memory.
+pub fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finalize().into());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Compares `document_hash` with what `entry` notarized.
+pub fn verify_entry(entry: &JournalEntry, document_hash: [u8; 32]) -> Result<Verification, NotaryError> {
+    let (Some(notarization), Some(seal)) = (&entry.notarization, &entry.seal) else {
+        return Err(NotaryError::NotNotarized);
+    };
+    Ok(Verification {
+        matches: notarization.sha256 == document_hash,
+        document_hash,
+        recorded_hash: notarization.sha256,
+        slot: seal.slot,
+        recorded_at: seal.sealed_at,
+        uri: notarization.uri.clone(),
+        mime: notarization.mime.clone(),
+    })
+}
+
+/// Fetches the entry at `address` and checks the file at `path` against it.
+pub fn verify_file(
+    connection: &(impl JournalConnection + ?Sized),
+    address: &Pubkey,
+    path: &Path,
+) -> Result<Verification, NotaryError> {
+    let document_hash = hash_file(path)?;
+    let entry = match accounts::fetch::<JournalEntry>(connection, address) {
+        Ok(Some(entry)) => entry,
+        Ok(None) | Err(ClientError::InvalidAccount(_)) => return Err(NotaryError::NotAnEntry),
+        Err(err) => return Err(NotaryError::Rpc(err.to_string())),
+    };
+    verify_entry(&entry, document_hash)
+}
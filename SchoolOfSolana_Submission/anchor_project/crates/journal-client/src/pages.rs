@@ -0,0 +1,65 @@
+//! Splitting long-form text across an entry's message and its `EntryPage`s.
+
+use journal_dapp::states::{MESSAGE_LENGTH, PAGE_LENGTH};
+
+/// Text of a long-form entry, laid out the way it is stored on-chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongForm {
+    /// Opening text, stored in `JournalEntry::message`.
+    pub message: String,
+    /// Remaining text, one item per `EntryPage` in reading order.
+    pub pages: Vec<String>,
+}
+
+impl LongForm {
+    /// Fills the message first, then as many pages as the rest of `text` needs.
+    pub fn split(text: &str) -> Self {
+        Self::split_to(text, MESSAGE_LENGTH, PAGE_LENGTH)
+    }
+
+    /// Like `split`, with the message and each page holding at most
+    /// `message_len` and `page_len` bytes.
+    pub fn split_to(text: &str, message_len: usize, page_len: usize) -> Self {
+        let (message, rest) = split_at_boundary(text, message_len);
+        Self {
+            message: message.to_string(),
+            pages: split_text(rest, page_len),
+        }
+    }
+
+    /// Reassembles the original text.
+    pub fn join(&self) -> String {
+        join_pages(&self.message, &self.pages)
+    }
+}
+
+/// Splits `text` into chunks of at most `max_len` bytes, never cutting a
+/// UTF-8 character in half.
+pub fn split_text(text: &str, max_len: usize) -> Vec<String> {
+    assert!(max_len >= 4, "chunks must fit any UTF-8 character");
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (chunk, tail) = split_at_boundary(rest, max_len);
+        chunks.push(chunk.to_string());
+        rest = tail;
+    }
+    chunks
+}
+
+/// Concatenates an entry's message and its pages in reading order.
+pub fn join_pages<S: AsRef<str>>(message: &str, pages: &[S]) -> String {
+    let mut text = String::from(message);
+    for page in pages {
+        text.push_str(page.as_ref());
+    }
+    text
+}
+
+fn split_at_boundary(text: &str, max_len: usize) -> (&str, &str) {
+    let mut end = text.len().min(max_len);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.split_at(end)
+}
@@ -0,0 +1,68 @@
+//! Addresses of the program's accounts, derived the same way the program
+//! checks them.
+
+use journal_dapp::states::*;
+use solana_sdk::pubkey::Pubkey;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &journal_dapp::ID).0
+}
+
+pub fn user_journal(owner: &Pubkey) -> Pubkey {
+    find(&[USER_JOURNAL_SEED.as_bytes(), owner.as_ref()])
+}
+
+pub fn journal_page(owner: &Pubkey, index: u64) -> Pubkey {
+    find(&[JOURNAL_PAGE_SEED.as_bytes(), owner.as_ref(), &index.to_le_bytes()])
+}
+
+/// Entry titled `title` in notebook `notebook`, 0 meaning none.
+pub fn entry(owner: &Pubkey, title: &str, notebook: u32) -> Pubkey {
+    find(&[ENTRY_SEED.as_bytes(), &entry_seed(title, notebook), owner.as_ref()])
+}
+
+/// Address of an entry, from the title and notebook it records.
+pub fn entry_of(journal_entry: &JournalEntry) -> Pubkey {
+    entry(&journal_entry.owner, &journal_entry.title, journal_entry.notebook)
+}
+
+/// Entry created before titles were hashed into the seeds; see `migrate_entry`.
+pub fn legacy_entry(owner: &Pubkey, title: &str) -> Pubkey {
+    find(&[title.as_bytes(), owner.as_ref()])
+}
+
+pub fn revision(entry: &Pubkey, revision: u32) -> Pubkey {
+    find(&[REVISION_SEED.as_bytes(), entry.as_ref(), &revision.to_le_bytes()])
+}
+
+pub fn entry_page(entry: &Pubkey, page_id: u16) -> Pubkey {
+    find(&[ENTRY_PAGE_SEED.as_bytes(), entry.as_ref(), &page_id.to_le_bytes()])
+}
+
+pub fn entry_reader(entry: &Pubkey, reader: &Pubkey) -> Pubkey {
+    find(&[READER_SEED.as_bytes(), entry.as_ref(), reader.as_ref()])
+}
+
+pub fn delegate(owner: &Pubkey, delegate: &Pubkey) -> Pubkey {
+    find(&[DELEGATE_SEED.as_bytes(), owner.as_ref(), delegate.as_ref()])
+}
+
+pub fn public_feed() -> Pubkey {
+    find(&[PUBLIC_FEED_SEED.as_bytes()])
+}
+
+pub fn feed_page(index: u64) -> Pubkey {
+    find(&[FEED_PAGE_SEED.as_bytes(), &index.to_le_bytes()])
+}
+
+pub fn notebook(owner: &Pubkey, id: u32) -> Pubkey {
+    find(&[NOTEBOOK_SEED.as_bytes(), owner.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn tag_index(owner: &Pubkey, tag: &str) -> Pubkey {
+    find(&[TAG_SEED.as_bytes(), owner.as_ref(), tag.as_bytes()])
+}
+
+pub fn transfer_offer(entry: &Pubkey) -> Pubkey {
+    find(&[TRANSFER_SEED.as_bytes(), entry.as_ref()])
+}
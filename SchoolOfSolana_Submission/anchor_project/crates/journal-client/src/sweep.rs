@@ -0,0 +1,222 @@
+//! Driving the "forget me" flow that closes every account of a journal.
+//!
+//! `close_all_entries` handles the entries of one index page per call, so the
+//! sweep walks the pages and packs as many entries into each transaction as
+//! fit, and `close_journal` then takes the index pages and notebooks a batch
+//! at a time. Every step re-reads on-chain state, so an interrupted sweep
+//! resumes by running it again. Sealed and notarized entries are closed like
+//! any other.
+
+use std::fmt;
+
+use journal_dapp::states::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::accounts::{self, fetch};
+use crate::connection::{ClientError, JournalConnection};
+use crate::{instructions, pda};
+
+/// Accounts beyond the fixed ones that one `close_all_entries` call may
+/// carry while staying within the transaction size limit.
+pub const MAX_SWEEP_ACCOUNTS: usize = 24;
+
+#[derive(Debug)]
+pub enum SweepError {
+    /// An RPC request or transaction failed.
+    Rpc(String),
+    /// An account the sweep depends on does not decode.
+    InvalidAccount(Pubkey),
+    /// The entry has too many pages, readers, tags and revisions to close in
+    /// one transaction; remove some of them first.
+    TooManyAccounts(Pubkey),
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepError::Rpc(reason) => write!(f, "rpc request failed: {reason}"),
+            SweepError::InvalidAccount(address) => write!(f, "account {address} does not decode"),
+            SweepError::TooManyAccounts(entry) => {
+                write!(f, "entry {entry} has too many child accounts to close in one transaction")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+impl From<ClientError> for SweepError {
+    fn from(err: ClientError) -> Self {
+        match err {
+            ClientError::InvalidAccount(address) => SweepError::InvalidAccount(address),
+            err => SweepError::Rpc(err.to_string()),
+        }
+    }
+}
+
+/// Reported after every `close_all_entries` transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SweepProgress {
+    pub page_index: u64,
+    pub closed: usize,
+    /// Entries the owner still has.
+    pub entries_left: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub entries_closed: u64,
+    /// Whether there was a journal to close.
+    pub journal_closed: bool,
+}
+
+/// Everything one entry takes with it: the entry, the accounts
+/// `close_all_entries` closes alongside, and its notebook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryClosure {
+    pub entry: Pubkey,
+    /// The entry followed by its transfer offer, pages, readers, tag indexes
+    /// and revisions.
+    pub accounts: Vec<Pubkey>,
+    pub notebook: Option<Pubkey>,
+}
+
+/// Groups closures into transactions of at most `max_accounts` remaining
+/// accounts, notebooks included, keeping their order.
+pub fn pack_closures(closures: Vec<EntryClosure>, max_accounts: usize) -> Result<Vec<Vec<EntryClosure>>, SweepError> {
+    let mut batches: Vec<Vec<EntryClosure>> = Vec::new();
+    let mut current: Vec<EntryClosure> = Vec::new();
+    for closure in closures {
+        if batch_size(std::slice::from_ref(&closure)) > max_accounts {
+            return Err(SweepError::TooManyAccounts(closure.entry));
+        }
+        current.push(closure);
+        if batch_size(&current) > max_accounts || current.len() > u8::MAX as usize {
+            let overflow = current.pop().expect("just pushed");
+            batches.push(std::mem::replace(&mut current, vec![overflow]));
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    Ok(batches)
+}
+
+fn batch_size(batch: &[EntryClosure]) -> usize {
+    let notebooks = distinct_notebooks(batch);
+    batch.iter().map(|closure| closure.accounts.len()).sum::<usize>() + notebooks.len()
+}
+
+fn distinct_notebooks(batch: &[EntryClosure]) -> Vec<Pubkey> {
+    let mut notebooks: Vec<Pubkey> = Vec::new();
+    for notebook in batch.iter().filter_map(|closure| closure.notebook) {
+        if !notebooks.contains(&notebook) {
+            notebooks.push(notebook);
+        }
+    }
+    notebooks
+}
+
+/// Closes every entry of `owner`, then their delegates and transfer offers,
+/// and finally the journal itself. Public entries are withdrawn from the
+/// feed first.
+pub fn sweep_journal(
+    connection: &(impl JournalConnection + ?Sized),
+    owner: &Keypair,
+    mut on_progress: impl FnMut(&SweepProgress),
+) -> Result<SweepReport, SweepError> {
+    let owner_key = owner.pubkey();
+    let mut report = SweepReport::default();
+    let user_journal_address = pda::user_journal(&owner_key);
+    let Some(user_journal) = fetch::<UserJournal>(connection, &user_journal_address)? else {
+        return Ok(report);
+    };
+
+    let mut pages = Vec::new();
+    for page_index in 0..user_journal.page_count() {
+        let page_address = pda::journal_page(&owner_key, page_index);
+        let Some(journal_page) = fetch::<JournalPage>(connection, &page_address)? else {
+            continue;
+        };
+        let mut entries = Vec::new();
+        for entry in journal_page.entries.iter().filter(|entry| **entry != Pubkey::default()) {
+            let journal_entry =
+                fetch::<JournalEntry>(connection, entry)?.ok_or(SweepError::InvalidAccount(*entry))?;
+            entries.push((*entry, journal_entry));
+        }
+        pages.push((page_index, entries));
+    }
+    for (page_index, entries) in pages {
+        let mut closures = Vec::new();
+        for (entry, journal_entry) in &entries {
+            if let Some(feed_page) = journal_entry.feed_page {
+                let make_private = instructions::set_visibility(journal_entry, feed_page, Visibility::Private);
+                connection.send(&[make_private], &[owner])?;
+            }
+            closures.push(entry_closure(connection, *entry, journal_entry)?);
+        }
+
+        let mut batches = pack_closures(closures, MAX_SWEEP_ACCOUNTS)?;
+        if batches.is_empty() && page_index != user_journal.current_page() {
+            // An empty page other than the current one is closed on its own.
+            batches.push(Vec::new());
+        }
+        for batch in batches {
+            let accounts: Vec<Pubkey> = batch
+                .iter()
+                .flat_map(|closure| closure.accounts.iter().copied())
+                .chain(distinct_notebooks(&batch))
+                .collect();
+            let close = instructions::close_all_entries(&owner_key, page_index, batch.len() as u8, &accounts);
+            connection.send(&[close], &[owner])?;
+            report.entries_closed += batch.len() as u64;
+            let user_journal = fetch::<UserJournal>(connection, &user_journal_address)?
+                .ok_or(SweepError::InvalidAccount(user_journal_address))?;
+            on_progress(&SweepProgress {
+                page_index,
+                closed: batch.len(),
+                entries_left: user_journal.entry_count,
+            });
+        }
+    }
+
+    for (_, delegate) in accounts::delegates_of(connection, &owner_key)? {
+        connection.send(&[instructions::revoke_delegate(&owner_key, &delegate.delegate)], &[owner])?;
+    }
+    for (_, offer) in accounts::offers_from(connection, &owner_key)? {
+        connection.send(&[instructions::cancel_transfer(&owner_key, &offer.entry)], &[owner])?;
+    }
+
+    while let Some(user_journal) = fetch::<UserJournal>(connection, &user_journal_address)? {
+        let close = instructions::close_journal(&owner_key, &user_journal, MAX_SWEEP_ACCOUNTS);
+        connection.send(&[close], &[owner])?;
+    }
+    report.journal_closed = true;
+    Ok(report)
+}
+
+/// Collects the accounts `close_all_entries` needs to close `entry`.
+fn entry_closure(
+    connection: &(impl JournalConnection + ?Sized),
+    entry: Pubkey,
+    journal_entry: &JournalEntry,
+) -> Result<EntryClosure, SweepError> {
+    let readers: Vec<Pubkey> = accounts::entry_readers(connection, &entry)?
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    let revisions: Vec<Pubkey> = accounts::entry_revisions(connection, &entry)?
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    let mut accounts = vec![entry, pda::transfer_offer(&entry)];
+    accounts.extend(instructions::entry_children(journal_entry, &readers, &revisions));
+    let notebook = (journal_entry.notebook != 0).then(|| pda::notebook(&journal_entry.owner, journal_entry.notebook));
+    Ok(EntryClosure {
+        entry,
+        accounts,
+        notebook,
+    })
+}
@@ -0,0 +1,1086 @@
+#[cfg(test)]
+mod support {
+    use crate::vm::TestVm;
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    /// A new wallet holding 10 SOL on `vm`.
+    pub fn funded(vm: &TestVm) -> Keypair {
+        let keypair = Keypair::new();
+        vm.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+        keypair
+    }
+}
+
+#[cfg(test)]
+mod pages_tests {
+    use crate::pages::*;
+    use journal_dapp::states::{MESSAGE_LENGTH, PAGE_LENGTH};
+
+    #[test]
+    fn short_text_stays_in_message() {
+        let long_form = LongForm::split("Dear diary");
+
+        assert_eq!(long_form.message, "Dear diary");
+        assert!(long_form.pages.is_empty());
+    }
+
+    #[test]
+    fn long_text_round_trips() {
+        let text = "All work and no play. ".repeat(200);
+        let long_form = LongForm::split(&text);
+
+        assert_eq!(long_form.message.len(), MESSAGE_LENGTH);
+        assert!(long_form.pages.iter().all(|page| page.len() <= PAGE_LENGTH));
+        assert_eq!(long_form.join(), text);
+    }
+
+    #[test]
+    fn split_respects_char_boundaries() {
+        // 3-byte characters never line up with a 1000-byte chunk
+        let text = "日本語".repeat(700);
+        let chunks = split_text(&text, PAGE_LENGTH);
+
+        assert!(chunks.iter().all(|chunk| chunk.len() <= PAGE_LENGTH));
+        assert_eq!(chunks[0].len(), 999);
+        assert_eq!(join_pages("", &chunks), text);
+    }
+
+    #[test]
+    fn empty_text_has_no_pages() {
+        assert!(split_text("", PAGE_LENGTH).is_empty());
+        assert_eq!(LongForm::split("").join(), "");
+    }
+}
+
+#[cfg(test)]
+mod crypto_tests {
+    use crate::crypto::*;
+    use journal_dapp::states::{EncryptionScheme, AUTH_TAG_LENGTH, MESSAGE_LENGTH};
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn round_trip() {
+        let owner = Keypair::new();
+        let key = EntryKey::derive(&owner).unwrap();
+
+        let content = key.encrypt("meet me at noon").unwrap();
+        assert_eq!(content.ciphertext.len(), "meet me at noon".len() + AUTH_TAG_LENGTH);
+        assert_eq!(key.decrypt(&content).unwrap(), "meet me at noon");
+    }
+
+    #[test]
+    fn derivation_is_deterministic() {
+        let owner = Keypair::new();
+        let content = EntryKey::derive(&owner).unwrap().encrypt("same key").unwrap();
+
+        let again = EntryKey::derive(&owner).unwrap();
+        assert_eq!(again.decrypt(&content).unwrap(), "same key");
+    }
+
+    #[test]
+    fn other_keypair_cannot_decrypt() {
+        let content = EntryKey::derive(&Keypair::new()).unwrap().encrypt("private").unwrap();
+        let stranger = EntryKey::derive(&Keypair::new()).unwrap();
+
+        assert!(matches!(stranger.decrypt(&content), Err(CryptoError::Decryption)));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let key = EntryKey::derive(&Keypair::new()).unwrap();
+        let mut content = key.encrypt("untouched").unwrap();
+        content.ciphertext[0] ^= 1;
+
+        assert!(matches!(key.decrypt(&content), Err(CryptoError::Decryption)));
+    }
+
+    #[test]
+    fn oversized_message_is_rejected() {
+        let key = EntryKey::derive(&Keypair::new()).unwrap();
+
+        assert!(matches!(
+            key.encrypt(&"x".repeat(MESSAGE_LENGTH + 1)),
+            Err(CryptoError::MessageTooLong)
+        ));
+    }
+
+    #[test]
+    fn shared_key_round_trip() {
+        let reader = Keypair::new();
+        let content_key = EntryKey::generate();
+        let content = content_key.encrypt("for your eyes").unwrap();
+        assert_eq!(content.scheme, EncryptionScheme::XChaCha20Poly1305Envelope);
+
+        let wrapped = content_key.wrap_for(&reader.pubkey()).unwrap();
+        let unwrapped = EntryKey::unwrap_with(&reader, &wrapped).unwrap();
+        assert_eq!(unwrapped.decrypt(&content).unwrap(), "for your eyes");
+    }
+
+    #[test]
+    fn wrapped_key_is_bound_to_reader() {
+        let wrapped = EntryKey::generate().wrap_for(&Keypair::new().pubkey()).unwrap();
+
+        assert!(matches!(
+            EntryKey::unwrap_with(&Keypair::new(), &wrapped),
+            Err(CryptoError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn derived_key_cannot_be_shared() {
+        let key = EntryKey::derive(&Keypair::new()).unwrap();
+
+        assert!(matches!(
+            key.wrap_for(&Keypair::new().pubkey()),
+            Err(CryptoError::NotShareable)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod events_tests {
+    use crate::events::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::Event;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use journal_dapp::events::{EntryCreated, EntryDeleted};
+
+    fn data_line(data: &[u8]) -> String {
+        format!("Program data: {}", STANDARD.encode(data))
+    }
+
+    fn created() -> EntryCreated {
+        EntryCreated {
+            owner: Pubkey::new_unique(),
+            entry: Pubkey::new_unique(),
+            title_hash: [7; 32],
+            title_len: 5,
+            content_len: 42,
+            encrypted: false,
+            slot: 99,
+        }
+    }
+
+    #[test]
+    fn decodes_events_in_order() {
+        let deleted = EntryDeleted {
+            owner: Pubkey::new_unique(),
+            entry: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            title_hash: [1; 32],
+            slot: 100,
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", journal_dapp::ID),
+            "Program log: Instruction: CreateEntry".to_string(),
+            data_line(&created().data()),
+            data_line(&deleted.data()),
+            format!("Program {} success", journal_dapp::ID),
+        ];
+
+        let events = parse_logs(&logs).unwrap();
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            JournalEvent::EntryCreated(event) => {
+                assert_eq!(event.content_len, 42);
+                assert_eq!(event.slot, 99);
+            }
+            other => panic!("unexpected {}", other.name()),
+        }
+        assert!(matches!(&events[1], JournalEvent::EntryDeleted(event) if event.authority == deleted.authority));
+    }
+
+    #[test]
+    fn ignores_data_from_other_programs() {
+        let other = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {other} invoke [1]"),
+            data_line(&created().data()),
+            format!("Program {} invoke [2]", journal_dapp::ID),
+            data_line(&created().data()),
+            format!("Program {} success", journal_dapp::ID),
+            data_line(&created().data()),
+            format!("Program {other} success"),
+        ];
+
+        assert_eq!(parse_logs(&logs).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn skips_unknown_and_rejects_malformed_events() {
+        let mut truncated = created().data();
+        truncated.truncate(20);
+        let invoke = format!("Program {} invoke [1]", journal_dapp::ID);
+
+        let unknown = vec![invoke.clone(), data_line(&[0; 16])];
+        assert!(parse_logs(&unknown).unwrap().is_empty());
+
+        let malformed = vec![invoke, data_line(&truncated)];
+        assert!(matches!(parse_logs(&malformed), Err(EventError::Malformed("EntryCreated"))));
+    }
+}
+
+#[cfg(test)]
+mod notary_tests {
+    use crate::notary::*;
+    use crate::vm::TestVm;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AccountSerialize;
+    use journal_dapp::states::{JournalEntry, Notarization, Seal};
+    use sha2::{Digest, Sha256};
+    use solana_sdk::account::Account;
+
+    fn notarized(sha256: [u8; 32]) -> JournalEntry {
+        let mut entry = JournalEntry::new(Pubkey::new_unique(), "Lease".to_string(), String::new(), 1_700_000_000);
+        entry.notarization = Some(Notarization {
+            sha256,
+            uri: Some("ipfs://lease.pdf".to_string()),
+            mime: None,
+        });
+        entry.seal = Some(Seal {
+            slot: 42,
+            sealed_at: 1_700_000_000,
+            content_hash: sha256,
+        });
+        entry
+    }
+
+    #[test]
+    fn file_matches_its_notarized_hash() {
+        let path = std::env::temp_dir().join(format!("journal-notary-{}", std::process::id()));
+        std::fs::write(&path, b"signed lease").unwrap();
+        let document_hash = hash_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(document_hash, <[u8; 32]>::from(Sha256::digest(b"signed lease")));
+
+        let verification = verify_entry(&notarized(document_hash), document_hash).unwrap();
+        assert!(verification.matches);
+        assert_eq!(verification.slot, 42);
+        assert_eq!(verification.uri.as_deref(), Some("ipfs://lease.pdf"));
+    }
+
+    #[test]
+    fn other_document_does_not_match() {
+        let verification = verify_entry(&notarized([1; 32]), [2; 32]).unwrap();
+
+        assert!(!verification.matches);
+        assert_eq!(verification.recorded_hash, [1; 32]);
+    }
+
+    #[test]
+    fn plain_entry_is_not_notarized() {
+        let entry = JournalEntry::new(Pubkey::new_unique(), "Diary".to_string(), "hi".to_string(), 0);
+
+        assert!(matches!(verify_entry(&entry, [0; 32]), Err(NotaryError::NotNotarized)));
+    }
+
+    #[test]
+    fn entry_forged_by_another_program_is_rejected() {
+        let path = std::env::temp_dir().join(format!("journal-forged-{}", std::process::id()));
+        std::fs::write(&path, b"forged lease").unwrap();
+        let mut data = Vec::new();
+        notarized(hash_file(&path).unwrap()).try_serialize(&mut data).unwrap();
+        let vm = TestVm::new();
+        let (genuine, forged) = (Pubkey::new_unique(), Pubkey::new_unique());
+        vm.set_account(genuine, Account { data: data.clone(), ..Account::new(1, 0, &journal_dapp::ID) });
+        vm.set_account(forged, Account { data, ..Account::new(1, 0, &Pubkey::new_unique()) });
+
+        let genuine = verify_file(&vm, &genuine, &path);
+        let forged = verify_file(&vm, &forged, &path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(genuine.unwrap().matches);
+        assert!(matches!(forged, Err(NotaryError::NotAnEntry)));
+    }
+}
+
+#[cfg(test)]
+mod sweep_tests {
+    use crate::sweep::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn closure(children: usize, notebook: Option<Pubkey>) -> EntryClosure {
+        let entry = Pubkey::new_unique();
+        let mut accounts = vec![entry];
+        accounts.extend((0..children).map(|_| Pubkey::new_unique()));
+        EntryClosure {
+            entry,
+            accounts,
+            notebook,
+        }
+    }
+
+    #[test]
+    fn packs_entries_up_to_the_account_limit() {
+        let closures: Vec<_> = (0..10).map(|_| closure(2, None)).collect();
+        let batches = pack_closures(closures, 9).unwrap();
+
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 3, 3, 1]);
+    }
+
+    #[test]
+    fn shared_notebook_is_counted_once() {
+        let notebook = Some(Pubkey::new_unique());
+        let closures = vec![closure(0, notebook), closure(0, notebook), closure(0, notebook)];
+        let batches = pack_closures(closures, 4).unwrap();
+
+        assert_eq!(batches.len(), 1);
+    }
+
+    #[test]
+    fn oversized_entry_is_reported() {
+        let big = closure(30, None);
+        let entry = big.entry;
+
+        assert!(matches!(
+            pack_closures(vec![closure(0, None), big], MAX_SWEEP_ACCOUNTS),
+            Err(SweepError::TooManyAccounts(address)) if address == entry
+        ));
+    }
+}
+
+#[cfg(test)]
+mod sdk_tests {
+    use crate::accounts::{
+        all_entries_by_owner, entries_by_owner, entries_by_tag, entry_pages, entry_readers, entry_revisions,
+        fetch, find_entry_page,
+    };
+    use crate::connection::JournalConnection;
+    use crate::crypto::EntryKey;
+    use crate::events::{parse_logs, JournalEvent};
+    use crate::instructions;
+    use crate::pda;
+    use crate::sweep::sweep_journal;
+    use crate::tests::support::funded;
+    use crate::vm::TestVm;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::{AnchorSerialize, Discriminator};
+    use journal_dapp::errors::JournalError;
+    use journal_dapp::states::*;
+    use solana_sdk::account::Account;
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    fn create(vm: &TestVm, owner: &Keypair, title: &str, message: &str, tags: &[String]) -> JournalEntry {
+        let owner_key = owner.pubkey();
+        let page_index = fetch::<UserJournal>(vm, &pda::user_journal(&owner_key))
+            .unwrap()
+            .map_or(0, |user_journal| user_journal.current_page());
+        let create = instructions::create_entry(&owner_key, page_index, 0, title, message, tags);
+        vm.send(&[create], &[owner]).unwrap();
+        fetch(vm, &pda::entry(&owner_key, title, 0)).unwrap().unwrap()
+    }
+
+    #[test]
+    fn created_entry_is_fetched_and_listed() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        create(&vm, &alice, "Monday", "Rainy", &[]);
+        create(&vm, &alice, "Tuesday", "Sunny", &[]);
+        create(&vm, &bob, "Monday", "Someone else's", &[]);
+
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry(&alice.pubkey(), "monday", 0)).unwrap().unwrap();
+        assert_eq!(entry.message, "Rainy");
+        let mut titles: Vec<String> = entries_by_owner(&vm, &alice.pubkey())
+            .unwrap()
+            .into_iter()
+            .map(|(_, entry)| entry.title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, ["Monday", "Tuesday"]);
+        assert_eq!(find_entry_page(&vm, &alice.pubkey(), &pda::entry_of(&entry)).unwrap(), Some(0));
+
+        vm.send(&[instructions::archive_entry(&entry, None)], &[&alice]).unwrap();
+        assert_eq!(entries_by_owner(&vm, &alice.pubkey()).unwrap().len(), 1);
+        let all = all_entries_by_owner(&vm, &alice.pubkey()).unwrap();
+        assert_eq!(all.iter().filter(|(_, entry)| entry.archived_at.is_some()).count(), 1);
+    }
+
+    #[test]
+    fn legacy_entry_with_an_invalid_title_is_not_migrated() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        for title in ["Groceries", "Bell\u{7}"] {
+            let legacy = LegacyJournalEntry {
+                owner: owner_key,
+                title: title.to_string(),
+                message: "Eggs".to_string(),
+            };
+            let mut data = JournalEntry::DISCRIMINATOR.to_vec();
+            legacy.serialize(&mut data).unwrap();
+            let account = Account { data, ..Account::new(LAMPORTS_PER_SOL, 0, &journal_dapp::ID) };
+            vm.set_account(pda::legacy_entry(&owner_key, title), account);
+        }
+
+        vm.send(&[instructions::migrate_entry(&owner_key, 0, "Groceries")], &[&owner]).unwrap();
+        let err = vm
+            .send(&[instructions::migrate_entry(&owner_key, 0, "Bell\u{7}")], &[&owner])
+            .unwrap_err();
+
+        assert_eq!(err.custom_code(), Some(JournalError::TitleControlCharacter.into()));
+        assert!(vm.get_account(&pda::legacy_entry(&owner_key, "Bell\u{7}")).unwrap().is_some());
+        assert_eq!(entries_by_owner(&vm, &owner_key).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn archived_entry_pages_cannot_be_removed() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let entry = create(&vm, &owner, "Old notes", "Start", &[]);
+        vm.send(&[instructions::append_page(&entry, "More")], &[&owner]).unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        vm.send(&[instructions::archive_entry(&entry, None)], &[&owner]).unwrap();
+
+        let err = vm.send(&[instructions::remove_page(&entry, 0)], &[&owner]).unwrap_err();
+
+        assert_eq!(err.custom_code(), Some(JournalError::EntryArchived.into()));
+    }
+
+    #[test]
+    fn update_emits_events_and_maintains_tag_indexes() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let entry = create(&vm, &owner, "Garden", "Planted tomatoes", &["outdoors".to_string()]);
+
+        let tags = ["plants".to_string()];
+        vm.send(&[instructions::update_entry(&owner.pubkey(), &entry, "Planted basil", Some(&tags))], &[&owner])
+            .unwrap();
+
+        let events = parse_logs(&vm.logs()).unwrap();
+        assert!(matches!(events.as_slice(), [JournalEvent::EntryUpdated(_)]));
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        assert_eq!(entry.message, "Planted basil");
+        assert_eq!(entry.tags, tags);
+        let outdoors = pda::tag_index(&owner.pubkey(), "outdoors");
+        assert!(fetch::<TagIndex>(&vm, &outdoors).unwrap().is_none());
+        let plants = fetch::<TagIndex>(&vm, &pda::tag_index(&owner.pubkey(), "plants")).unwrap().unwrap();
+        assert_eq!(plants.entries, [pda::entry_of(&entry)]);
+    }
+
+    #[test]
+    fn entries_by_tag_reads_only_the_tagged_entries() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let walks = ["walks".to_string()];
+        create(&vm, &owner, "Ridge", "Windy", &walks);
+        create(&vm, &owner, "Errands", "Milk", &[]);
+        create(&vm, &owner, "Coast", "Calm", &walks);
+
+        let titles: Vec<String> = entries_by_tag(&vm, &owner.pubkey(), "walks")
+            .unwrap()
+            .into_iter()
+            .map(|(_, entry)| entry.title)
+            .collect();
+        assert_eq!(titles, ["Ridge", "Coast"]);
+        assert!(entries_by_tag(&vm, &owner.pubkey(), "recipes").unwrap().is_empty());
+    }
+
+    #[test]
+    fn renamed_and_moved_entries_stay_tagged() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        let tags = ["travel".to_string(), "todo".to_string()];
+        let entry = create(&vm, &owner, "Lisbon", "Trams and tiles", &tags);
+        let tagged = |tag: &str| fetch::<TagIndex>(&vm, &pda::tag_index(&owner_key, tag)).unwrap().unwrap().entries;
+
+        vm.send(&[instructions::rename_entry(&entry, 0, "Porto", &[], &[])], &[&owner]).unwrap();
+        let renamed = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Porto", 0)).unwrap().unwrap();
+        assert_eq!(tagged("travel"), [pda::entry_of(&renamed)]);
+        assert_eq!(tagged("todo"), [pda::entry_of(&renamed)]);
+
+        vm.send(&[instructions::create_notebook(&owner_key, 1, "Trips", "")], &[&owner]).unwrap();
+        vm.send(&[instructions::move_entry(&renamed, 0, 1, &[], &[])], &[&owner]).unwrap();
+        let moved = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Porto", 1)).unwrap().unwrap();
+        assert_eq!(moved.tags, tags);
+        assert_eq!(tagged("travel"), [pda::entry_of(&moved)]);
+        assert_eq!(tagged("todo"), [pda::entry_of(&moved)]);
+    }
+
+    #[test]
+    fn transferred_entry_moves_to_the_recipients_tag_index() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        let tags = ["recipes".to_string()];
+        let entry = create(&vm, &alice, "Bread", "Flour, water, salt", &tags);
+
+        vm.send(&[instructions::offer_transfer(&entry, &bob.pubkey())], &[&alice]).unwrap();
+        vm.send(&[instructions::accept_transfer(&bob.pubkey(), &entry, 0, 0)], &[&bob]).unwrap();
+
+        assert!(fetch::<TagIndex>(&vm, &pda::tag_index(&alice.pubkey(), "recipes")).unwrap().is_none());
+        let recipes = fetch::<TagIndex>(&vm, &pda::tag_index(&bob.pubkey(), "recipes")).unwrap().unwrap();
+        assert_eq!(recipes.entries, [pda::entry(&bob.pubkey(), "Bread", 0)]);
+    }
+
+    #[test]
+    fn deleting_an_entry_closes_its_transfer_offer() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        let entry = create(&vm, &alice, "Keys", "Under the mat", &[]);
+        let offer = pda::transfer_offer(&pda::entry_of(&entry));
+        vm.send(&[instructions::offer_transfer(&entry, &bob.pubkey())], &[&alice]).unwrap();
+
+        vm.send(&[instructions::delete_entry(&alice.pubkey(), &entry, 0, &[], &[])], &[&alice])
+            .unwrap();
+
+        assert!(fetch::<TransferOffer>(&vm, &offer).unwrap().is_none());
+        let events = parse_logs(&vm.logs()).unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [JournalEvent::TransferCancelled(_), JournalEvent::EntryDeleted(_)]
+        ));
+    }
+
+    #[test]
+    fn renaming_an_entry_closes_its_transfer_offer() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        let entry = create(&vm, &alice, "Handbook", "First edition", &[]);
+        let offer = pda::transfer_offer(&pda::entry_of(&entry));
+        vm.send(&[instructions::offer_transfer(&entry, &bob.pubkey())], &[&alice]).unwrap();
+        vm.send(&[instructions::rename_entry(&entry, 0, "Manual", &[], &[])], &[&alice]).unwrap();
+
+        assert!(fetch::<TransferOffer>(&vm, &offer).unwrap().is_none());
+        let events = parse_logs(&vm.logs()).unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [JournalEvent::TransferCancelled(_), JournalEvent::EntryRenamed(_)]
+        ));
+        vm.warp_to(vm.clock().unix_timestamp + 60);
+        let later = create(&vm, &alice, "Handbook", "Second edition", &[]);
+        vm.send(&[instructions::accept_transfer(&bob.pubkey(), &later, 0, 0)], &[&bob])
+            .unwrap_err();
+    }
+
+    #[test]
+    fn renamed_and_moved_entries_keep_their_pages_revisions_and_readers() {
+        let vm = TestVm::new();
+        let (owner, reader) = (funded(&vm), Keypair::new());
+        let owner_key = owner.pubkey();
+        let entry = create(&vm, &owner, "Lisbon", "Day one", &[]);
+        vm.send(&[instructions::set_history_mode(&entry, true)], &[&owner]).unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        vm.send(&[instructions::update_entry(&owner_key, &entry, "Day two", None)], &[&owner])
+            .unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        vm.send(&[instructions::append_page(&entry, "Trams and tiles")], &[&owner]).unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+
+        let stored: Vec<u32> = entry_revisions(&vm, &pda::entry_of(&entry))
+            .unwrap()
+            .into_iter()
+            .map(|(_, revision)| revision.revision)
+            .collect();
+        vm.send(&[instructions::rename_entry(&entry, 0, "Porto", &[], &[])], &[&owner])
+            .unwrap_err();
+        vm.send(&[instructions::rename_entry(&entry, 0, "Porto", &[], &stored)], &[&owner])
+            .unwrap();
+        let renamed = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Porto", 0)).unwrap().unwrap();
+        let entry_pages = entry_pages(&vm, &renamed).unwrap();
+        assert_eq!(entry_pages[0].content, "Trams and tiles");
+        let revisions = entry_revisions(&vm, &pda::entry_of(&renamed)).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].1.message, "Day one");
+        assert!(entry_revisions(&vm, &pda::entry_of(&entry)).unwrap().is_empty());
+
+        let key = EntryKey::generate();
+        let content = key.encrypt("Meet at noon").unwrap();
+        vm.send(&[instructions::create_encrypted_entry(&owner_key, 0, 0, "Plans", content)], &[&owner])
+            .unwrap();
+        let plans = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Plans", 0)).unwrap().unwrap();
+        let wrapped = key.wrap_for(&reader.pubkey()).unwrap();
+        vm.send(&[instructions::grant_reader(&plans, &reader.pubkey(), wrapped)], &[&owner])
+            .unwrap();
+        let plans = fetch::<JournalEntry>(&vm, &pda::entry_of(&plans)).unwrap().unwrap();
+        vm.send(&[instructions::create_notebook(&owner_key, 1, "Work", "")], &[&owner]).unwrap();
+        vm.send(&[instructions::move_entry(&plans, 0, 1, &[reader.pubkey()], &[])], &[&owner])
+            .unwrap();
+
+        let moved = pda::entry(&owner_key, "Plans", 1);
+        let grant = fetch::<EntryReader>(&vm, &pda::entry_reader(&moved, &reader.pubkey())).unwrap().unwrap();
+        assert_eq!(grant.entry, moved);
+        let moved = fetch::<JournalEntry>(&vm, &moved).unwrap().unwrap();
+        let unwrapped = EntryKey::unwrap_with(&reader, &grant.wrapped_key).unwrap();
+        assert_eq!(unwrapped.decrypt(moved.encrypted.as_ref().unwrap()).unwrap(), "Meet at noon");
+        assert!(entry_readers(&vm, &pda::entry_of(&plans)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failed_transaction_reports_the_program_error_and_changes_nothing() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let entry = create(&vm, &owner, "Contract", "Final terms", &[]);
+        vm.send(&[instructions::seal_entry(&entry)], &[&owner]).unwrap();
+        let sealed = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+
+        let err = vm
+            .send(&[instructions::update_entry(&owner.pubkey(), &sealed, "Altered terms", None)], &[&owner])
+            .unwrap_err();
+
+        assert_eq!(err.custom_code(), Some(JournalError::EntrySealed.into()));
+        let unchanged = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        assert_eq!(unchanged.message, "Final terms");
+    }
+
+    #[test]
+    fn missing_signature_is_rejected() {
+        let vm = TestVm::new();
+        let (owner, intruder) = (funded(&vm), funded(&vm));
+        let entry = create(&vm, &owner, "Secret", "Mine", &[]);
+
+        assert!(vm.send(&[instructions::archive_entry(&entry, None)], &[&intruder]).is_err());
+        assert!(vm.send(&[instructions::archive_entry(&entry, None)], &[&owner]).is_ok());
+    }
+
+    #[test]
+    fn batches_create_and_delete_entries() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        let new_entries: Vec<NewEntry> = (0..3)
+            .map(|day| NewEntry {
+                title: format!("Day {day}"),
+                message: "Walked the dog".to_string(),
+            })
+            .collect();
+        vm.send(&[instructions::create_entries(&owner_key, 0, &new_entries)], &[&owner])
+            .unwrap();
+        assert_eq!(entries_by_owner(&vm, &owner_key).unwrap().len(), 3);
+
+        let walks = ["walks".to_string()];
+        let kept = create(&vm, &owner, "Day 3", "Walked the dog again", &walks);
+        let tagged = create(&vm, &owner, "Day 4", "Walked in the rain", &walks);
+
+        let listed = entries_by_owner(&vm, &owner_key).unwrap();
+        let mut doomed: Vec<(&JournalEntry, u64)> = listed
+            .iter()
+            .filter(|(_, entry)| entry.tags.is_empty())
+            .take(2)
+            .map(|(_, entry)| (entry, 0))
+            .collect();
+        doomed.push((&tagged, 0));
+        vm.send(&[instructions::delete_entries(&owner_key, &doomed)], &[&owner])
+            .unwrap();
+
+        let user_journal = fetch::<UserJournal>(&vm, &pda::user_journal(&owner_key)).unwrap().unwrap();
+        assert_eq!(user_journal.entry_count, 2);
+        assert_eq!(entries_by_owner(&vm, &owner_key).unwrap().len(), 2);
+        let walks = fetch::<TagIndex>(&vm, &pda::tag_index(&owner_key, "walks")).unwrap().unwrap();
+        assert_eq!(walks.entries, [pda::entry_of(&kept)]);
+    }
+
+    #[test]
+    fn deleting_an_entry_closes_its_revisions() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        let keep_history = |entry: &JournalEntry, message: &str| {
+            vm.send(&[instructions::set_history_mode(entry, true)], &[&owner]).unwrap();
+            let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(entry)).unwrap().unwrap();
+            vm.send(&[instructions::update_entry(&owner_key, &entry, message, None)], &[&owner])
+                .unwrap();
+            fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap()
+        };
+        let entry = keep_history(&create(&vm, &owner, "Diary", "Day one", &[]), "Day two");
+        let revisions: Vec<_> = entry_revisions(&vm, &pda::entry_of(&entry))
+            .unwrap()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+        assert_eq!(revisions.len(), 1);
+
+        vm.send(&[instructions::delete_entry(&owner_key, &entry, 0, &[], &[])], &[&owner])
+            .unwrap_err();
+        vm.send(&[instructions::delete_entry(&owner_key, &entry, 0, &[], &revisions)], &[&owner])
+            .unwrap();
+
+        assert!(vm.get_account(&revisions[0]).unwrap().is_none());
+        // A new entry under the same title snapshots into the same addresses.
+        let entry = keep_history(&create(&vm, &owner, "Diary", "Day one, again", &[]), "Day two, again");
+        assert_eq!(entry.history_len, 1);
+    }
+
+    #[test]
+    fn rotated_key_is_rewrapped_for_remaining_readers() {
+        let vm = TestVm::new();
+        let (owner, kept, revoked) = (funded(&vm), Keypair::new(), Keypair::new());
+        let owner_key = owner.pubkey();
+        let first_key = EntryKey::generate();
+        let create = instructions::create_encrypted_entry(
+            &owner_key,
+            0,
+            0,
+            "Plans",
+            first_key.encrypt("Meet at noon").unwrap(),
+        );
+        vm.send(&[create], &[&owner]).unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Plans", 0)).unwrap().unwrap();
+        for reader in [&kept, &revoked] {
+            let wrapped = first_key.wrap_for(&reader.pubkey()).unwrap();
+            vm.send(&[instructions::grant_reader(&entry, &reader.pubkey(), wrapped)], &[&owner])
+                .unwrap();
+        }
+
+        let second_key = EntryKey::generate();
+        let rotate = [
+            instructions::revoke_reader(&entry, &revoked.pubkey()),
+            instructions::update_encrypted_entry(&entry, second_key.encrypt("Meet at one").unwrap()),
+            instructions::grant_reader(&entry, &kept.pubkey(), second_key.wrap_for(&kept.pubkey()).unwrap()),
+        ];
+        vm.send(&rotate, &[&owner]).unwrap();
+
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        assert_eq!(entry.reader_count, 1);
+        let content = entry.encrypted.as_ref().unwrap();
+        let grant = fetch::<EntryReader>(&vm, &pda::entry_reader(&pda::entry_of(&entry), &kept.pubkey()))
+            .unwrap()
+            .unwrap();
+        let unwrapped = EntryKey::unwrap_with(&kept, &grant.wrapped_key).unwrap();
+        assert_eq!(unwrapped.decrypt(content).unwrap(), "Meet at one");
+        assert!(first_key.decrypt(content).is_err());
+    }
+
+    #[test]
+    fn deleting_a_shared_entry_closes_its_reader_grants() {
+        let vm = TestVm::new();
+        let (owner, reader) = (funded(&vm), Keypair::new());
+        let owner_key = owner.pubkey();
+        let key = EntryKey::generate();
+        let content = key.encrypt("Meet at noon").unwrap();
+        vm.send(&[instructions::create_encrypted_entry(&owner_key, 0, 0, "Plans", content)], &[&owner])
+            .unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry(&owner_key, "Plans", 0)).unwrap().unwrap();
+        let wrapped = key.wrap_for(&reader.pubkey()).unwrap();
+        vm.send(&[instructions::grant_reader(&entry, &reader.pubkey(), wrapped)], &[&owner])
+            .unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+
+        let readers: Vec<Pubkey> = entry_readers(&vm, &pda::entry_of(&entry))
+            .unwrap()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+        assert_eq!(readers, [pda::entry_reader(&pda::entry_of(&entry), &reader.pubkey())]);
+        vm.send(&[instructions::delete_entry(&owner_key, &entry, 0, &readers, &[])], &[&owner])
+            .unwrap();
+
+        assert!(vm.get_account(&readers[0]).unwrap().is_none());
+        assert!(vm.get_account(&pda::entry_of(&entry)).unwrap().is_none());
+    }
+
+    #[test]
+    fn sweep_closes_the_whole_journal() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        let entry = create(&vm, &owner, "Diary", "Day one", &["personal".to_string()]);
+        vm.send(&[instructions::set_history_mode(&entry, true)], &[&owner]).unwrap();
+        let entry = fetch::<JournalEntry>(&vm, &pda::entry_of(&entry)).unwrap().unwrap();
+        vm.send(&[instructions::update_entry(&owner_key, &entry, "Day two", None)], &[&owner])
+            .unwrap();
+        create(&vm, &owner, "Notes", "Buy milk", &[]);
+        vm.send(&[instructions::set_delegate(&owner_key, &Keypair::new().pubkey(), DELEGATE_APPEND, None)], &[&owner])
+            .unwrap();
+
+        let mut progress = Vec::new();
+        let report = sweep_journal(&vm, &owner, |step| progress.push(*step)).unwrap();
+
+        assert_eq!(report.entries_closed, 2);
+        assert!(report.journal_closed);
+        assert_eq!(progress.last().unwrap().entries_left, 0);
+        assert!(vm.get_program_accounts(Vec::new()).unwrap().is_empty());
+        assert!(vm.get_account(&pda::user_journal(&owner_key)).unwrap().is_none());
+    }
+
+    #[test]
+    fn sweep_closes_more_notebooks_than_fit_in_one_transaction() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        for id in 1..=30 {
+            vm.send(&[instructions::create_notebook(&owner_key, id, &format!("Volume {id}"), "")], &[&owner])
+                .unwrap();
+        }
+        create(&vm, &owner, "Index", "See the volumes", &[]);
+
+        let report = sweep_journal(&vm, &owner, |_| {}).unwrap();
+
+        assert_eq!(report.entries_closed, 1);
+        assert!(vm.get_program_accounts(Vec::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn partial_close_journal_reports_what_is_left() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        for id in 1..=30 {
+            vm.send(&[instructions::create_notebook(&owner_key, id, &format!("Volume {id}"), "")], &[&owner])
+                .unwrap();
+        }
+        let user_journal = fetch::<UserJournal>(&vm, &pda::user_journal(&owner_key)).unwrap().unwrap();
+
+        vm.send(&[instructions::close_journal(&owner_key, &user_journal, 10)], &[&owner])
+            .unwrap();
+
+        let events = parse_logs(&vm.logs()).unwrap();
+        let [JournalEvent::JournalClosing(closing)] = events.as_slice() else {
+            panic!("expected a single JournalClosing event");
+        };
+        assert_eq!((closing.closed_notebooks, closing.notebooks_left), (10, 20));
+        assert!(fetch::<UserJournal>(&vm, &pda::user_journal(&owner_key)).unwrap().is_some());
+    }
+
+    #[test]
+    fn sweep_closes_sealed_and_notarized_entries() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let owner_key = owner.pubkey();
+        create(&vm, &owner, "Scratch", "Delete me", &[]);
+        let will = create(&vm, &owner, "Will", "Everything to the cat", &[]);
+        vm.send(&[instructions::seal_entry(&will)], &[&owner]).unwrap();
+        vm.send(&[instructions::notarize(&owner_key, 0, 0, "Lease", [7; 32], None, None)], &[&owner])
+            .unwrap();
+
+        let report = sweep_journal(&vm, &owner, |_| {}).unwrap();
+
+        assert_eq!(report.entries_closed, 3);
+        assert!(report.journal_closed);
+        assert!(vm.get_program_accounts(Vec::new()).unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod compose_tests {
+    use crate::accounts::fetch;
+    use crate::compose::*;
+    use crate::pda;
+    use crate::tests::support::funded;
+    use crate::vm::TestVm;
+    use journal_dapp::states::*;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn long_text_is_written_across_pages() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let text = "It was a dark and stormy night. ".repeat(100);
+
+        let entry = create_entry(&vm, &owner, 0, "Novel", &text, &["fiction".to_string()]).unwrap();
+
+        assert!(entry.message.len() < MESSAGE_LENGTH);
+        assert!(entry.pages.len() >= 3);
+        assert_eq!(entry_text(&vm, &entry).unwrap(), text);
+    }
+
+    #[test]
+    fn rewrite_touches_only_changed_pages() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let text = "a".repeat(4000);
+        let entry = create_entry(&vm, &owner, 0, "Draft", &text, &[]).unwrap();
+        let first_page = pda::entry_page(&pda::entry_of(&entry), entry.pages[0]);
+        let first_before = fetch::<EntryPage>(&vm, &first_page).unwrap().unwrap();
+
+        let shorter = format!("{}b", &text[..3000]);
+        let tags = ["edited".to_string()];
+        let entry = rewrite_entry(&vm, &owner, &entry, &shorter, Some(&tags)).unwrap();
+
+        assert_eq!(entry_text(&vm, &entry).unwrap(), shorter);
+        assert_eq!(entry.tags, tags);
+        assert_eq!(entry.revision, 1);
+        let first_after = fetch::<EntryPage>(&vm, &first_page).unwrap().unwrap();
+        assert_eq!(first_after.content, first_before.content);
+
+        let unchanged = rewrite_entry(&vm, &owner, &entry, &shorter, None).unwrap();
+        assert_eq!(unchanged.revision, 1);
+    }
+
+    #[test]
+    fn oversized_text_is_rejected_before_sending() {
+        let vm = TestVm::new();
+        let owner = funded(&vm);
+        let text = "x".repeat(MAX_PAGES * PAGE_LENGTH + MESSAGE_LENGTH);
+
+        let result = create_entry(&vm, &owner, 0, "Tome", &text, &[]);
+
+        assert!(matches!(result, Err(ComposeError::TooLong { .. })));
+        assert!(fetch::<JournalEntry>(&vm, &pda::entry(&owner.pubkey(), "Tome", 0)).unwrap().is_none());
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use crate::accounts::entries_by_owner;
+    use crate::compose::{create_entry, entry_text};
+    use crate::connection::JournalConnection;
+    use crate::export::*;
+    use crate::instructions;
+    use crate::tests::support::funded;
+    use crate::vm::TestVm;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    fn note(title: &str, text: &str) -> ExportedEntry {
+        ExportedEntry {
+            title: title.to_string(),
+            text: text.to_string(),
+            tags: Vec::new(),
+            notebook: 0,
+            owner: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn full() -> ExportedEntry {
+        ExportedEntry {
+            tags: vec!["travel".to_string(), "2024".to_string()],
+            notebook: 7,
+            owner: Some(Keypair::new().pubkey()),
+            created_at: Some(1_792_332_189),
+            updated_at: Some(1_792_332_250),
+            ..note("Trip: \"Lisbon\" — day 1", "---\nTrams, tiles and pastéis.\n\n")
+        }
+    }
+
+    #[test]
+    fn timestamps_format_as_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(-1), "1969-12-31T23:59:59Z");
+        assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(rfc3339(1_792_332_189), "2026-10-18T14:03:09Z");
+        for time in [-1, 0, 951_782_400, 1_792_332_189] {
+            assert_eq!(parse_rfc3339(&rfc3339(time)), Ok(time));
+        }
+        assert!(parse_rfc3339("2026-13-01T00:00:00Z").is_err());
+        assert!(parse_rfc3339("2026-10-18 14:03:09").is_err());
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let entry = full();
+        let markdown = to_markdown(&entry);
+
+        assert!(markdown.starts_with("---\ntitle: \"Trip: \\\"Lisbon\\\" — day 1\"\n"));
+        assert!(markdown.contains("created_at: 2026-10-18T14:03:09Z\n"));
+        assert_eq!(from_markdown("ignored", &markdown), Ok(entry));
+    }
+
+    #[test]
+    fn hand_written_note_is_titled_after_its_file() {
+        let parsed = from_markdown("groceries", "- eggs\n- milk\n").unwrap();
+        assert_eq!(parsed, note("groceries", "- eggs\n- milk\n"));
+
+        let parsed = from_markdown("x", "---\ntitle: 'It''s done'\ntags: [home, chores]\n---\nSwept.").unwrap();
+        assert_eq!(parsed.title, "It's done");
+        assert_eq!(parsed.tags, ["home", "chores"]);
+        assert_eq!(parsed.text, "Swept.");
+        assert!(from_markdown("x", "---\ncreated_at: yesterday\n---\n").is_err());
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let entries = vec![full(), note("Plain", "")];
+
+        assert_eq!(from_json(&to_json(&entries)), Ok(entries));
+        assert!(from_json("{\"version\": 99, \"entries\": []}").is_err());
+        assert!(from_json("[]").is_err());
+    }
+
+    #[test]
+    fn files_round_trip_in_both_formats() {
+        let dir = std::env::temp_dir().join(format!("journal-export-{}", Keypair::new().pubkey()));
+        let mut same_day = full();
+        same_day.text = "Again".to_string();
+        let entries = vec![full(), same_day, note("Undated", "text")];
+
+        write_export(&dir.join("markdown"), Format::Markdown, &entries).unwrap();
+        write_export(&dir.join("archive.json"), Format::Json, &entries).unwrap();
+        let mut names: Vec<String> = std::fs::read_dir(dir.join("markdown"))
+            .unwrap()
+            .map(|file| file.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+
+        assert_eq!(
+            names,
+            ["2026-10-18-trip-lisbon-day-1-2.md", "2026-10-18-trip-lisbon-day-1.md", "undated.md"]
+        );
+        let mut from_markdown = read_export(&dir.join("markdown")).unwrap();
+        from_markdown.sort_by(|a, b| a.text.cmp(&b.text));
+        let mut expected = entries.clone();
+        expected.sort_by(|a, b| a.text.cmp(&b.text));
+        assert_eq!(from_markdown, expected);
+        assert_eq!(read_export(&dir.join("archive.json")).unwrap(), entries);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn import_skips_taken_titles_and_moves_entries_between_wallets() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        let long = "Dear diary. ".repeat(200);
+        create_entry(&vm, &alice, 0, "Monday", &long, &["daily".to_string()]).unwrap();
+        create_entry(&vm, &alice, 0, "Tuesday", "Short", &[]).unwrap();
+        create_entry(&vm, &bob, 0, "monday", "Bob's own", &[]).unwrap();
+
+        let export = export_entries(&vm, &alice.pubkey()).unwrap();
+        assert_eq!(export.entries.len(), 2);
+        assert_eq!(export.entries[0].text, long);
+        let mut archive = export.entries.clone();
+        archive.push(note("TUESDAY", "Repeated in the archive"));
+
+        let plan = plan_import(&vm, &bob.pubkey(), archive.clone()).unwrap();
+        let titles = |entries: &[ExportedEntry]| entries.iter().map(|entry| entry.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&plan.create), ["Tuesday"]);
+        assert_eq!(titles(&plan.duplicates), ["Monday", "TUESDAY"]);
+        assert_eq!(entries_by_owner(&vm, &bob.pubkey()).unwrap().len(), 1);
+
+        let mut created = Vec::new();
+        import_entries(&vm, &bob, &plan, |entry| created.push(entry.title.clone())).unwrap();
+        assert_eq!(created, ["Tuesday"]);
+        let bobs = entries_by_owner(&vm, &bob.pubkey()).unwrap();
+        assert_eq!(bobs.len(), 2);
+        let (_, tuesday) = bobs.iter().find(|(_, entry)| entry.title == "Tuesday").unwrap();
+        assert_eq!(entry_text(&vm, tuesday).unwrap(), "Short");
+
+        let replanned = plan_import(&vm, &bob.pubkey(), archive).unwrap();
+        assert!(replanned.create.is_empty());
+    }
+
+    #[test]
+    fn notebook_entries_import_outside_notebooks_the_wallet_lacks() {
+        let vm = TestVm::new();
+        let (alice, bob) = (funded(&vm), funded(&vm));
+        vm.send(&[instructions::create_notebook(&alice.pubkey(), 1, "Travel", "")], &[&alice]).unwrap();
+        create_entry(&vm, &alice, 1, "Lisbon", "Trams", &[]).unwrap();
+        create_entry(&vm, &alice, 0, "Lisbon", "Outside the notebook", &[]).unwrap();
+        create_entry(&vm, &alice, 1, "Porto", "Bridges", &[]).unwrap();
+
+        let mut archive = export_entries(&vm, &alice.pubkey()).unwrap().entries;
+        archive.sort_by(|a, b| (a.notebook, &a.title).cmp(&(b.notebook, &b.title)));
+        let plan = plan_import(&vm, &bob.pubkey(), archive).unwrap();
+        assert_eq!(plan.create.iter().map(|entry| entry.notebook).collect::<Vec<_>>(), [0, 0]);
+        assert_eq!(plan.duplicates.len(), 1);
+        assert_eq!(plan.unfiled, [("Porto".to_string(), 1)]);
+
+        import_entries(&vm, &bob, &plan, |_| ()).unwrap();
+        let bobs = entries_by_owner(&vm, &bob.pubkey()).unwrap();
+        assert_eq!(bobs.len(), 2);
+        assert!(bobs.iter().all(|(_, entry)| entry.notebook == 0));
+
+        vm.send(&[instructions::create_notebook(&bob.pubkey(), 1, "Trips", "")], &[&bob]).unwrap();
+        let faro = ExportedEntry { notebook: 1, ..note("Faro", "Beach") };
+        let plan = plan_import(&vm, &bob.pubkey(), vec![faro]).unwrap();
+        assert_eq!(plan.create[0].notebook, 1);
+        assert!(plan.unfiled.is_empty());
+        import_entries(&vm, &bob, &plan, |_| ()).unwrap();
+        assert_eq!(entries_by_owner(&vm, &bob.pubkey()).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,656 @@
+//! An in-process cluster for tests.
+//!
+//! `TestVm` runs `journal_dapp` natively, the way `solana-program-test` does
+//! without a BPF build: each instruction's accounts are serialized in the
+//! loader's input format, handed to the program's `entry`, and read back
+//! from the buffer afterwards. Syscalls go through `program_stubs`, which
+//! serve the clock and rent sysvars, collect logs and carry out the system
+//! program instructions the program invokes.
+//!
+//! Like the runtime, the VM checks signatures and signer and writable flags,
+//! that only an account's owner program changes its data, size or owner or
+//! debits it, that data grows by at most `MAX_PERMITTED_DATA_INCREASE` per
+//! instruction, that lamports balance, and that accounts a transaction
+//! writes end up rent-exempt or empty. Program changes are checked at every
+//! CPI as well as at the end of each instruction. A failed transaction
+//! leaves no trace. Fees and compute units are not modelled.
+//!
+//! Only built for the crate's own tests; it is not part of the published API.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_rpc_client_api::filter::Memcmp;
+use solana_sdk::account::Account;
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::{self, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::hash::hashv;
+use solana_sdk::instruction::{CompiledInstruction, Instruction, InstructionError};
+use solana_sdk::message::Message;
+use solana_sdk::native_loader;
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_sdk::program_utils::limited_deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+use solana_sdk::system_instruction::{SystemError, SystemInstruction};
+use solana_sdk::system_program;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use crate::connection::{ClientError, JournalConnection};
+
+const SUCCESS: u64 = 0;
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    /// Accounts of the running instruction as of its last CPI, which each
+    /// program's changes are checked against.
+    static SYNCED: RefCell<HashMap<Pubkey, Account>> = RefCell::new(HashMap::new());
+    /// A runtime check that failed during a CPI, which fails the instruction
+    /// whatever the program makes of it.
+    static CPI_ERROR: RefCell<Option<InstructionError>> = const { RefCell::new(None) };
+}
+
+fn log(line: String) {
+    LOGS.with(|logs| logs.borrow_mut().push(line));
+}
+
+pub struct TestVm {
+    state: RefCell<VmState>,
+}
+
+struct VmState {
+    accounts: HashMap<Pubkey, Account>,
+    clock: Clock,
+    logs: Vec<String>,
+    transaction_count: u64,
+}
+
+impl Default for TestVm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestVm {
+    /// A cluster with `journal_dapp` deployed and nothing else.
+    pub fn new() -> Self {
+        static INSTALL_STUBS: Once = Once::new();
+        INSTALL_STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Syscalls));
+        });
+
+        let executable = |owner| Account {
+            lamports: 1,
+            data: Vec::new(),
+            owner,
+            executable: true,
+            rent_epoch: 0,
+        };
+        let accounts = HashMap::from([
+            (system_program::ID, executable(native_loader::ID)),
+            (journal_dapp::ID, executable(bpf_loader_upgradeable::ID)),
+        ]);
+        Self {
+            state: RefCell::new(VmState {
+                accounts,
+                clock: Clock {
+                    slot: 1,
+                    unix_timestamp: 1_700_000_000,
+                    ..Clock::default()
+                },
+                logs: Vec::new(),
+                transaction_count: 0,
+            }),
+        }
+    }
+
+    /// Credits `lamports` to `address`, creating a system account if needed.
+    pub fn airdrop(&self, address: &Pubkey, lamports: u64) {
+        let mut state = self.state.borrow_mut();
+        let account = state
+            .accounts
+            .entry(*address)
+            .or_insert_with(|| Account::new(0, 0, &system_program::ID));
+        account.lamports += lamports;
+    }
+
+    pub fn set_account(&self, address: Pubkey, account: Account) {
+        self.state.borrow_mut().accounts.insert(address, account);
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.state.borrow().clock.clone()
+    }
+
+    /// Moves the clock to `unix_timestamp`, advancing the slot as well.
+    pub fn warp_to(&self, unix_timestamp: i64) {
+        let mut state = self.state.borrow_mut();
+        state.clock.slot += 1;
+        state.clock.unix_timestamp = unix_timestamp;
+    }
+
+    /// Log messages of the most recent transaction, in the format a
+    /// validator reports them.
+    pub fn logs(&self) -> Vec<String> {
+        self.state.borrow().logs.clone()
+    }
+
+    fn process(&self, transaction: &Transaction) -> Result<(), TransactionError> {
+        let mut state = self.state.borrow_mut();
+        state.transaction_count += 1;
+        CLOCK.with(|clock| *clock.borrow_mut() = state.clock.clone());
+        LOGS.with(|logs| logs.borrow_mut().clear());
+
+        let message = &transaction.message;
+        let mut accounts = state.accounts.clone();
+        let result = message.instructions.iter().enumerate().try_for_each(|(index, instruction)| {
+            execute(message, instruction, &mut accounts)
+                .map_err(|err| TransactionError::InstructionError(index as u8, err))
+        });
+        let result = result.and_then(|()| check_rent(message, &state.accounts, &accounts));
+        state.logs = LOGS.with(|logs| logs.take());
+        if result.is_ok() {
+            accounts.retain(|_, account| account.lamports > 0);
+            state.accounts = accounts;
+        }
+        result
+    }
+}
+
+impl JournalConnection for TestVm {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>, ClientError> {
+        Ok(self.state.borrow().accounts.get(address).cloned())
+    }
+
+    fn get_program_accounts(&self, filters: Vec<Memcmp>) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        let state = self.state.borrow();
+        let mut matches: Vec<(Pubkey, Account)> = state
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.owner == journal_dapp::ID)
+            .filter(|(_, account)| filters.iter().all(|filter| filter.bytes_match(&account.data)))
+            .map(|(address, account)| (*address, account.clone()))
+            .collect();
+        matches.sort_by_key(|(address, _)| *address);
+        Ok(matches)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature, ClientError> {
+        let payer = signers.first().map(|signer| signer.pubkey());
+        let mut transaction = Transaction::new_with_payer(instructions, payer.as_ref());
+        let count = self.state.borrow().transaction_count;
+        transaction
+            .try_sign(signers, hashv(&[b"test-vm", &count.to_le_bytes()]))
+            .map_err(|err| ClientError::Rpc(err.to_string()))?;
+        match self.process(&transaction) {
+            Ok(()) => Ok(transaction.signatures[0]),
+            Err(error) => Err(ClientError::Transaction {
+                error,
+                logs: self.logs(),
+            }),
+        }
+    }
+}
+
+/// Runs one top-level instruction against `accounts`.
+fn execute(
+    message: &Message,
+    instruction: &CompiledInstruction,
+    accounts: &mut HashMap<Pubkey, Account>,
+) -> Result<(), InstructionError> {
+    let program_id = message.account_keys[instruction.program_id_index as usize];
+    let metas: Vec<(Pubkey, bool, bool)> = instruction
+        .accounts
+        .iter()
+        .map(|&index| {
+            let index = index as usize;
+            (message.account_keys[index], message.is_signer(index), message.is_writable(index))
+        })
+        .collect();
+    let before: Vec<Account> = metas.iter().map(|(key, ..)| load(accounts, key)).collect();
+    SYNCED.with(|synced| {
+        *synced.borrow_mut() = metas
+            .iter()
+            .zip(&before)
+            .map(|((key, ..), account)| (*key, account.clone()))
+            .collect();
+    });
+    CPI_ERROR.with(|error| error.take());
+
+    log(format!("Program {program_id} invoke [1]"));
+    let mut input = Input::serialize(&metas, &before, &instruction.data, &program_id);
+    let result = {
+        // SAFETY: the buffer is laid out the way the loader lays it out, and
+        // the infos borrowing it are dropped before it is read back.
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(input.as_mut_ptr()) };
+        if *program_id == journal_dapp::ID {
+            journal_dapp::entry(program_id, &infos, data)
+        } else if *program_id == system_program::ID {
+            let infos: Vec<&AccountInfo> = infos.iter().collect();
+            run_system_instruction(data, &infos, |info| info.is_signer)
+        } else {
+            Err(ProgramError::IncorrectProgramId)
+        }
+    };
+    let result = match CPI_ERROR.with(|error| error.take()) {
+        Some(err) => Err(err),
+        None => result.map_err(|err| InstructionError::from(u64::from(err))),
+    };
+    let result = result.and_then(|()| {
+        let after = input.accounts();
+        let synced: Vec<Account> = SYNCED.with(|synced| {
+            let synced = synced.borrow();
+            metas.iter().map(|(key, ..)| synced[key].clone()).collect()
+        });
+        let writable: Vec<bool> = metas.iter().map(|(_, _, writable)| *writable).collect();
+        verify_changes(&program_id, &writable, &synced, &after)?;
+        verify_balance(&metas, &before, &after)?;
+        for ((key, ..), account) in metas.iter().zip(after) {
+            accounts.insert(*key, account);
+        }
+        Ok(())
+    });
+
+    match &result {
+        Ok(()) => {
+            if let Some((program, data)) = RETURN_DATA.with(|data| data.take()) {
+                log(format!("Program return: {program} {}", STANDARD.encode(data)));
+            }
+            log(format!("Program {program_id} success"));
+        }
+        Err(err) => log(format!("Program {program_id} failed: {err}")),
+    }
+    result
+}
+
+fn load(accounts: &HashMap<Pubkey, Account>, key: &Pubkey) -> Account {
+    accounts
+        .get(key)
+        .cloned()
+        .unwrap_or_else(|| Account::new(0, 0, &system_program::ID))
+}
+
+/// Applies the runtime's checks on what `program_id` may have changed between
+/// `before` and `after`, account by account.
+fn verify_changes(
+    program_id: &Pubkey,
+    writable: &[bool],
+    before: &[Account],
+    after: &[Account],
+) -> Result<(), InstructionError> {
+    for ((writable, old), new) in writable.iter().zip(before).zip(after) {
+        let owned = old.owner == *program_id;
+        if !writable && old.lamports != new.lamports {
+            return Err(InstructionError::ReadonlyLamportChange);
+        }
+        if !writable && (old.data != new.data || old.owner != new.owner) {
+            return Err(InstructionError::ReadonlyDataModified);
+        }
+        if old.owner != new.owner && (!owned || old.executable || new.data.iter().any(|byte| *byte != 0)) {
+            return Err(InstructionError::ModifiedProgramId);
+        }
+        if new.lamports < old.lamports && !owned {
+            return Err(InstructionError::ExternalAccountLamportSpend);
+        }
+        if old.data.len() != new.data.len() && !owned {
+            return Err(InstructionError::AccountDataSizeChanged);
+        }
+        if new.data.len() > old.data.len() + MAX_PERMITTED_DATA_INCREASE
+            || new.data.len() as u64 > MAX_PERMITTED_DATA_LENGTH
+        {
+            return Err(InstructionError::InvalidRealloc);
+        }
+        if old.data != new.data && !owned {
+            return Err(InstructionError::ExternalAccountDataModified);
+        }
+    }
+    Ok(())
+}
+
+/// Checks that an instruction neither minted nor burnt lamports.
+fn verify_balance(
+    metas: &[(Pubkey, bool, bool)],
+    before: &[Account],
+    after: &[Account],
+) -> Result<(), InstructionError> {
+    // Duplicates are counted once, at their first position.
+    let total = |accounts: &[Account]| {
+        accounts
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| !metas[..*position].iter().any(|(key, ..)| *key == metas[*position].0))
+            .map(|(_, account)| account.lamports as u128)
+            .sum::<u128>()
+    };
+    if total(before) != total(after) {
+        return Err(InstructionError::UnbalancedInstruction);
+    }
+    Ok(())
+}
+
+/// Fails a transaction that leaves a written account holding lamports but
+/// short of rent exemption, unless it already was and neither grew nor
+/// received lamports.
+fn check_rent(
+    message: &Message,
+    before: &HashMap<Pubkey, Account>,
+    after: &HashMap<Pubkey, Account>,
+) -> Result<(), TransactionError> {
+    let rent = Rent::default();
+    let rent_paying = |account: &Account| account.lamports > 0 && !rent.is_exempt(account.lamports, account.data.len());
+    for (index, key) in message.account_keys.iter().enumerate() {
+        let Some(new) = after.get(key).filter(|account| rent_paying(account)) else {
+            continue;
+        };
+        let allowed = before
+            .get(key)
+            .is_some_and(|old| rent_paying(old) && old.data.len() == new.data.len() && new.lamports <= old.lamports);
+        if message.is_writable(index) && !allowed {
+            return Err(TransactionError::InsufficientFundsForRent {
+                account_index: index as u8,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// An instruction's accounts and data in the loader's input format.
+struct Input {
+    buffer: Vec<u64>,
+    slots: Vec<Slot>,
+}
+
+/// Where an account sits in the input buffer.
+enum Slot {
+    Account {
+        /// Offset of the account's owner, followed by its lamports, data
+        /// length and data.
+        owner: usize,
+        rent_epoch: usize,
+        executable: bool,
+    },
+    /// Position of the first occurrence of the same account.
+    Duplicate(usize),
+}
+
+impl Input {
+    fn serialize(metas: &[(Pubkey, bool, bool)], accounts: &[Account], data: &[u8], program_id: &Pubkey) -> Self {
+        let mut bytes = Vec::new();
+        let mut slots = Vec::new();
+        bytes.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        for (position, ((key, signer, writable), account)) in metas.iter().zip(accounts).enumerate() {
+            if let Some(first) = metas[..position].iter().position(|(other, ..)| other == key) {
+                bytes.push(first as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                slots.push(Slot::Duplicate(first));
+                continue;
+            }
+            bytes.extend_from_slice(&[NON_DUP_MARKER, *signer as u8, *writable as u8, account.executable as u8]);
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(key.as_ref());
+            let owner = bytes.len();
+            bytes.extend_from_slice(account.owner.as_ref());
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            slots.push(Slot::Account {
+                owner,
+                rent_epoch: bytes.len(),
+                executable: account.executable,
+            });
+            bytes.extend_from_slice(&account.rent_epoch.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(program_id.as_ref());
+
+        // A u64 buffer keeps the lamport and length fields aligned.
+        let mut buffer = vec![0u64; bytes.len().div_ceil(8)];
+        for (word, chunk) in buffer.iter_mut().zip(bytes.chunks(8)) {
+            let mut padded = [0; 8];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_ne_bytes(padded);
+        }
+        Self { buffer, slots }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr() as *mut u8
+    }
+
+    /// The accounts as the instruction left them.
+    fn accounts(&self) -> Vec<Account> {
+        let bytes: Vec<u8> = self.buffer.iter().flat_map(|word| word.to_ne_bytes()).collect();
+        let read_u64 = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let mut accounts: Vec<Account> = Vec::new();
+        for slot in &self.slots {
+            let account = match *slot {
+                Slot::Account {
+                    owner,
+                    rent_epoch,
+                    executable,
+                } => {
+                    let data_len = read_u64(owner + 40) as usize;
+                    Account {
+                        lamports: read_u64(owner + 32),
+                        data: bytes[owner + 48..owner + 48 + data_len].to_vec(),
+                        owner: Pubkey::try_from(&bytes[owner..owner + 32]).unwrap(),
+                        executable,
+                        rent_epoch: read_u64(rent_epoch),
+                    }
+                }
+                Slot::Duplicate(first) => accounts[first].clone(),
+            };
+            accounts.push(account);
+        }
+        accounts
+    }
+}
+
+/// The syscalls `journal_dapp` makes when it runs natively.
+struct Syscalls;
+
+impl SyscallStubs for Syscalls {
+    fn sol_log(&self, message: &str) {
+        log(format!("Program log: {message}"));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        log(format!("Program data: {}", fields.join(" ")));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the sysvar getters pass a pointer to a `Clock`.
+        unsafe { *(var_addr as *mut Clock) = CLOCK.with(|clock| clock.borrow().clone()) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the sysvar getters pass a pointer to a `Rent`.
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the sysvar getters pass a pointer to an `EpochSchedule`.
+        unsafe { *(var_addr as *mut EpochSchedule) = EpochSchedule::default() };
+        SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = Some((journal_dapp::ID, data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|return_data| return_data.borrow().clone())
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let program_id = instruction.program_id;
+        log(format!("Program {program_id} invoke [2]"));
+        let result = sync_invoke(instruction, account_infos, signers_seeds);
+        match &result {
+            Ok(()) => log(format!("Program {program_id} success")),
+            Err(err) => log(format!("Program {program_id} failed: {err}")),
+        }
+        result
+    }
+}
+
+/// Runs a CPI the way the runtime does: the caller's changes to the passed
+/// accounts so far are checked and committed, the callee runs, and its
+/// changes are checked in turn.
+fn sync_invoke(instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+    let snapshot = |info: &AccountInfo| Account {
+        lamports: info.lamports(),
+        data: info.data.borrow().to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+        rent_epoch: info.rent_epoch,
+    };
+    let infos = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let fail = |err: InstructionError| {
+        CPI_ERROR.with(|error| *error.borrow_mut() = Some(err));
+        Err(ProgramError::Custom(0))
+    };
+    if instruction
+        .accounts
+        .iter()
+        .zip(&infos)
+        .any(|(meta, info)| meta.is_writable && !info.is_writable)
+    {
+        return fail(InstructionError::PrivilegeEscalation);
+    }
+
+    let synced: Vec<Account> = SYNCED.with(|synced| {
+        let synced = synced.borrow();
+        infos.iter().map(|info| synced[info.key].clone()).collect()
+    });
+    let before: Vec<Account> = infos.iter().map(|info| snapshot(info)).collect();
+    let writable: Vec<bool> = infos.iter().map(|info| info.is_writable).collect();
+    if let Err(err) = verify_changes(&journal_dapp::ID, &writable, &synced, &before) {
+        return fail(err);
+    }
+
+    invoke_system_program(instruction, &infos, signers_seeds)?;
+    let after: Vec<Account> = infos.iter().map(|info| snapshot(info)).collect();
+    let writable: Vec<bool> = instruction.accounts.iter().map(|meta| meta.is_writable).collect();
+    if let Err(err) = verify_changes(&system_program::ID, &writable, &before, &after) {
+        return fail(err);
+    }
+    SYNCED.with(|synced| {
+        let mut synced = synced.borrow_mut();
+        for (info, account) in infos.iter().zip(after) {
+            synced.insert(*info.key, account);
+        }
+    });
+    Ok(())
+}
+
+fn invoke_system_program(
+    instruction: &Instruction,
+    infos: &[&AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if instruction.program_id != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let signed = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &journal_dapp::ID))
+        .collect::<Result<Vec<_>, _>>()?;
+    run_system_instruction(&instruction.data, infos, |info| {
+        info.is_signer || signed.contains(info.key)
+    })
+}
+
+/// The subset of the system program that `journal_dapp` relies on.
+fn run_system_instruction(
+    data: &[u8],
+    accounts: &[&AccountInfo],
+    is_signer: impl Fn(&AccountInfo) -> bool,
+) -> ProgramResult {
+    let instruction: SystemInstruction =
+        limited_deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let account = |index: usize| accounts.get(index).copied().ok_or(ProgramError::NotEnoughAccountKeys);
+    let signer = |index: usize| {
+        let info = account(index)?;
+        if is_signer(info) {
+            Ok(info)
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    };
+    match instruction {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let (from, to) = (signer(0)?, signer(1)?);
+            if to.lamports() > 0 {
+                return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            to.assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Transfer { lamports } => transfer(signer(0)?, account(1)?, lamports),
+        SystemInstruction::Allocate { space } => allocate(signer(0)?, space),
+        SystemInstruction::Assign { owner } => {
+            let account = signer(0)?;
+            if *account.owner != system_program::ID {
+                return Err(ProgramError::IllegalOwner);
+            }
+            account.assign(&owner);
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if *from.owner != system_program::ID || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::Custom(SystemError::ResultWithNegativeLamports as u32))?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if *account.owner != system_program::ID || !account.data_is_empty() {
+        return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+    }
+    account.realloc(space as usize, true)
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum JournalError {
+    #[msg("Entry is not listed on the given journal page")]
+    EntryNotIndexed,
+    #[msg("Entry uses an old layout, call upgrade_entry first")]
+    OutdatedEntry,
+    #[msg("Entry already uses the current layout")]
+    EntryUpToDate,
+    #[msg("History mode is on, the revision account must be provided")]
+    RevisionAccountRequired,
+    #[msg("History mode is off, no revision account may be provided")]
+    HistoryDisabled,
+    #[msg("Every page, reader grant, tag index and stored revision of the entry must be passed to delete or move it")]
+    MissingChildAccounts,
+    #[msg("Revision does not belong to the entry")]
+    RevisionNotFound,
+    #[msg("Entry already has the maximum number of pages")]
+    TooManyPages,
+    #[msg("Page does not belong to this entry")]
+    PageNotFound,
+    #[msg("Ciphertext is shorter than its authentication tag")]
+    CiphertextTooShort,
+    #[msg("Ciphertext exceeds the maximum length")]
+    CiphertextTooLong,
+    #[msg("Entry is encrypted")]
+    EntryEncrypted,
+    #[msg("Entry is not encrypted")]
+    EntryNotEncrypted,
+    #[msg("Entry is not encrypted under a shareable content key")]
+    EntryNotShareable,
+    #[msg("Reader grant does not belong to this entry")]
+    ReaderNotFound,
+    #[msg("Signer is neither the owner nor a delegate with the required permission")]
+    NotAuthorized,
+    #[msg("Delegation has expired")]
+    DelegationExpired,
+    #[msg("Permissions must be a non-empty combination of append, edit and delete")]
+    InvalidPermissions,
+    #[msg("Expiry must lie in the future")]
+    InvalidExpiry,
+    #[msg("Title must not be empty")]
+    TitleEmpty,
+    #[msg("Title exceeds 50 characters")]
+    TitleTooLong,
+    #[msg("Title must not contain control characters")]
+    TitleControlCharacter,
+    #[msg("Title exceeds 50 bytes once UTF-8 encoded; use fewer non-ASCII characters")]
+    TitleTooLongUtf8,
+    #[msg("Message exceeds 1000 characters")]
+    MessageTooLong,
+    #[msg("Message exceeds 1000 bytes once UTF-8 encoded; use fewer non-ASCII characters")]
+    MessageTooLongUtf8,
+    #[msg("Page exceeds 1000 characters")]
+    PageTooLong,
+    #[msg("Page exceeds 1000 bytes once UTF-8 encoded; use fewer non-ASCII characters")]
+    PageTooLongUtf8,
+    #[msg("An entry with this title already exists")]
+    TitleTaken,
+    #[msg("Entry still has pages, reader grants or stored revisions")]
+    EntryHasChildren,
+    #[msg("Entry is archived, restore it first")]
+    EntryArchived,
+    #[msg("Entry is not archived")]
+    EntryNotArchived,
+    #[msg("The entry's grace period has not passed yet")]
+    GracePeriodActive,
+    #[msg("Entry is listed in the public feed, make it private or unlisted first")]
+    EntryPublic,
+    #[msg("Notebook name must not be empty")]
+    NotebookNameEmpty,
+    #[msg("Notebook name exceeds 50 bytes")]
+    NotebookNameTooLong,
+    #[msg("Notebook description exceeds 280 bytes")]
+    DescriptionTooLong,
+    #[msg("Notebook still holds entries")]
+    NotebookNotEmpty,
+    #[msg("The notebook account of the entry must be provided")]
+    NotebookRequired,
+    #[msg("Entry is already in this notebook")]
+    SameNotebook,
+    #[msg("Entry has more than 5 tags")]
+    TooManyTags,
+    #[msg("Tags must be 1-32 characters of a-z, 0-9, '-' and '_'")]
+    InvalidTag,
+    #[msg("Entry has the same tag twice")]
+    DuplicateTag,
+    #[msg("Tag index account does not match the tag")]
+    TagIndexMismatch,
+    #[msg("Tag index already lists the maximum number of entries")]
+    TagIndexFull,
+    #[msg("One tag index account per added or removed tag must be passed")]
+    MissingTagIndexes,
+    #[msg("Patch range lies outside the message")]
+    PatchOutOfBounds,
+    #[msg("Patch range splits a multi-byte character")]
+    PatchNotCharBoundary,
+    #[msg("Entry is sealed and can no longer be changed")]
+    EntrySealed,
+    #[msg("Every page of the entry must be passed in reading order")]
+    PagesRequired,
+    #[msg("Document URI exceeds 200 bytes")]
+    UriTooLong,
+    #[msg("MIME type exceeds 100 bytes")]
+    MimeTooLong,
+    #[msg("Entries can only be transferred to another wallet")]
+    InvalidRecipient,
+    #[msg("Transfer offer was made for an earlier entry at this address")]
+    StaleTransferOffer,
+    #[msg("Batch must contain at least one entry")]
+    EmptyBatch,
+    #[msg("Accounts passed do not match the batch")]
+    BatchAccountsMismatch,
+    #[msg("Journal still holds entries")]
+    JournalNotEmpty,
+}
@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+
+use crate::states::Visibility;
+
+// Every instruction that changes state emits at least one of these: one per
+// change, so `notarize` emits `EntryCreated` and `EntryNotarized`, and removing
+// or relocating an entry with an open offer also emits `TransferCancelled`.
+// A `close_journal` call that leaves part of the journal open emits
+// `JournalClosing`, the one that finishes `JournalClosed`.
+// `title_hash` is the `title_seed` of the entry's title, so feeds can group
+// events by title without storing titles; lengths are in bytes.
+
+#[event]
+pub struct EntryCreated {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub title_len: u16,
+    /// Length of the message, or of the ciphertext when `encrypted`.
+    pub content_len: u32,
+    pub encrypted: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryUpdated {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    /// The owner or the delegate that signed.
+    pub authority: Pubkey,
+    pub title_hash: [u8; 32],
+    pub content_len: u32,
+    pub revision: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryDeleted {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub authority: Pubkey,
+    pub title_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryArchived {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub purge_after: Option<i64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryRestored {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryPurged {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntrySealed {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub content_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryNotarized {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub sha256: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct VisibilitySet {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub visibility: Visibility,
+    /// Feed page listing the entry, if it is public.
+    pub feed_page: Option<u64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct NotebookCreated {
+    pub owner: Pubkey,
+    pub notebook: Pubkey,
+    pub id: u32,
+    pub name_len: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct NotebookRenamed {
+    pub owner: Pubkey,
+    pub notebook: Pubkey,
+    pub id: u32,
+    pub name_len: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct NotebookClosed {
+    pub owner: Pubkey,
+    pub notebook: Pubkey,
+    pub id: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryMoved {
+    pub owner: Pubkey,
+    pub old_entry: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub from_notebook: u32,
+    pub to_notebook: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryRenamed {
+    pub owner: Pubkey,
+    /// Previous address; equal to `entry` when only letter case or
+    /// surrounding whitespace changed.
+    pub old_entry: Pubkey,
+    pub entry: Pubkey,
+    pub old_title_hash: [u8; 32],
+    pub title_hash: [u8; 32],
+    pub title_len: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryMigrated {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub legacy_entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub content_len: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryUpgraded {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub content_len: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct HistoryModeSet {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub enabled: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RevisionRestored {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub restored_revision: u32,
+    /// The revision the restored message now has.
+    pub revision: u32,
+    pub content_len: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RevisionPruned {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub revision: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PageAppended {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub page_id: u16,
+    pub page_len: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PageReplaced {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub page_id: u16,
+    pub page_len: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PageRemoved {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub page_id: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ReaderGranted {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub reader: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ReaderRevoked {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub reader: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegateSet {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: u8,
+    pub expires_at: Option<i64>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct TransferOffered {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub recipient: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct TransferCancelled {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub recipient: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EntryTransferred {
+    /// The recipient, who now owns the entry.
+    pub owner: Pubkey,
+    pub previous_owner: Pubkey,
+    pub old_entry: Pubkey,
+    pub entry: Pubkey,
+    pub title_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct JournalSwept {
+    pub owner: Pubkey,
+    pub page_index: u64,
+    /// Entries closed by this call.
+    pub closed: u8,
+    /// Whether the index page was emptied and closed.
+    pub page_closed: bool,
+    /// Entries the owner still has across all pages.
+    pub entries_left: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct JournalClosed {
+    pub owner: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct JournalClosing {
+    pub owner: Pubkey,
+    /// Index pages and notebooks closed so far, this call included.
+    pub closed_pages: u64,
+    pub closed_notebooks: u32,
+    /// Index pages and notebooks still to close.
+    pub pages_left: u64,
+    pub notebooks_left: u32,
+    pub slot: u64,
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{index_tags, init_index, leave_notebook, rewrite_entry, unindex_tags};
+use crate::states::*;
+
+pub fn _accept_transfer<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptTransfer<'info>>, title: String) -> Result<()> {
+    let old_entry = ctx.accounts.journal_entry.key();
+    let tags = ctx.accounts.journal_entry.tags.clone();
+    if ctx.remaining_accounts.len() != 2 * tags.len() {
+        return err!(JournalError::MissingTagIndexes);
+    }
+    if !ctx.accounts.previous_page.remove(&old_entry) {
+        return err!(JournalError::EntryNotIndexed);
+    }
+    ctx.accounts.previous_journal.entry_count -= 1;
+    leave_notebook(&ctx.accounts.journal_entry, ctx.accounts.notebook.as_deref_mut())?;
+
+    let recipient = ctx.accounts.recipient.to_account_info();
+    let recipient_key = recipient.key();
+    init_index(
+        &mut ctx.accounts.user_journal,
+        &mut ctx.accounts.journal_page,
+        recipient_key,
+        ctx.bumps.user_journal,
+        ctx.bumps.journal_page,
+    );
+    ctx.accounts
+        .user_journal
+        .push_entry(&mut ctx.accounts.journal_page, ctx.accounts.new_entry.key());
+
+    // Tag indexes are per owner too, so the entry moves to the recipient's.
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let previous_owner = ctx.accounts.previous_owner.to_account_info();
+    let (previous_indexes, tag_indexes) = ctx.remaining_accounts.split_at(tags.len());
+    unindex_tags(old_entry, &previous_owner, &tags, previous_indexes, &system_program)?;
+    index_tags(
+        ctx.accounts.new_entry.key(),
+        recipient_key,
+        &tags,
+        tag_indexes,
+        &recipient,
+        &recipient,
+        &system_program,
+    )?;
+
+    // The entry lands outside any notebook, as notebook ids are per owner.
+    let mut transferred = ctx.accounts.journal_entry.clone().into_inner();
+    transferred.owner = recipient_key;
+    transferred.notebook = 0;
+    let title_seed = title_seed(&title);
+    rewrite_entry(
+        &mut ctx.accounts.journal_entry,
+        transferred,
+        &ctx.accounts.new_entry,
+        &[
+            ENTRY_SEED.as_bytes(),
+            title_seed.as_ref(),
+            recipient_key.as_ref(),
+            &[ctx.bumps.new_entry],
+        ],
+        &recipient,
+        &previous_owner,
+        &system_program,
+    )?;
+
+    emit!(EntryTransferred {
+        owner: recipient_key,
+        previous_owner: ctx.accounts.previous_owner.key(),
+        old_entry,
+        entry: ctx.accounts.new_entry.key(),
+        title_hash: title_seed,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Moves an offered entry under the recipient's seeds and index. The
+/// recipient pays for the new account and the previous owner gets back the
+/// rent of the old one and of the offer. Fails with `TitleTaken` when the
+/// recipient already has an entry with this title. `remaining_accounts`
+/// holds the previous owner's `TagIndex` of each of the entry's tags, in its
+/// order, followed by the recipient's.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct AcceptTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [
+            ENTRY_SEED.as_bytes(),
+            entry_seed(&title, journal_entry.notebook).as_ref(),
+            previous_owner.key().as_ref()
+        ],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump = transfer_offer.bump,
+        has_one = recipient,
+        constraint = transfer_offer.owner == previous_owner.key() @ JournalError::NotAuthorized,
+        constraint = transfer_offer.entry_created_at == journal_entry.created_at @ JournalError::StaleTransferOffer,
+        close = previous_owner,
+    )]
+    pub transfer_offer: Account<'info, TransferOffer>,
+
+    /// CHECK: address of the entry under the recipient, created in the handler
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), title_seed(&title).as_ref(), recipient.key().as_ref()],
+        bump,
+    )]
+    pub new_entry: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), previous_owner.key().as_ref()],
+        bump = previous_journal.bump,
+    )]
+    pub previous_journal: Account<'info, UserJournal>,
+
+    /// The previous owner's index page listing the entry.
+    #[account(
+        mut,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            previous_owner.key().as_ref(),
+            previous_page.index.to_le_bytes().as_ref()
+        ],
+        bump = previous_page.bump,
+    )]
+    pub previous_page: Account<'info, JournalPage>,
+
+    /// The entry's notebook; omitted for entries outside any notebook.
+    #[account(
+        mut,
+        seeds = [
+            NOTEBOOK_SEED.as_bytes(),
+            previous_owner.key().as_ref(),
+            journal_entry.notebook.to_le_bytes().as_ref()
+        ],
+        bump = notebook.bump,
+    )]
+    pub notebook: Option<Account<'info, Notebook>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), recipient.key().as_ref()],
+        bump,
+        payer = recipient,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            recipient.key().as_ref(),
+            user_journal.current_page().to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = recipient,
+        space = 8 + JournalPage::INIT_SPACE
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    #[account(mut)]
+    pub previous_owner: SystemAccount<'info>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
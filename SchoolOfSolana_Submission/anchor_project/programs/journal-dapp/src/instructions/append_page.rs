@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _append_page(ctx: Context<AppendPage>, content: String) -> Result<()> {
+    validate_page(&content)?;
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let entry_page = &mut ctx.accounts.entry_page;
+    entry_page.entry = journal_entry.key();
+    entry_page.page_id = journal_entry.next_page_id;
+    entry_page.content = content;
+    entry_page.bump = ctx.bumps.entry_page;
+
+    journal_entry.pages.push(entry_page.page_id);
+    journal_entry.next_page_id += 1;
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(PageAppended {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        page_id: entry_page.page_id,
+        page_len: entry_page.content.len() as u32,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, content: String)]
+pub struct AppendPage<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.encrypted.is_none() @ JournalError::EntryEncrypted,
+        constraint = journal_entry.pages.len() < MAX_PAGES @ JournalError::TooManyPages,
+        realloc = journal_entry.current_space() + 2,
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init,
+        seeds = [
+            ENTRY_PAGE_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            journal_entry.next_page_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = EntryPage::space(content.len())
+    )]
+    pub entry_page: Account<'info, EntryPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::instructions::write_message;
+use crate::states::*;
+
+pub fn _append_to_entry(ctx: Context<AppendToEntry>, text: String) -> Result<()> {
+    let message = ctx.accounts.journal_entry.message.clone() + &text;
+    write_message(
+        &mut ctx.accounts.journal_entry,
+        ctx.accounts.journal_revision.as_deref_mut(),
+        ctx.bumps.journal_revision,
+        message,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Adds `text` to the end of an entry's message without resending the rest,
+/// so devices appending concurrently don't overwrite each other. Needs
+/// `DELEGATE_APPEND` when a delegate signs.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct AppendToEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.encrypted.is_none() @ JournalError::EntryEncrypted,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// Receives the replaced message; required in history mode only.
+    #[account(
+        init,
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            journal_entry.revision.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = JournalRevision::space(journal_entry.message.len())
+    )]
+    pub journal_revision: Option<Account<'info, JournalRevision>>,
+
+    /// Grant held by `authority`; omitted when the owner signs.
+    #[account(
+        seeds = [DELEGATE_SEED.as_bytes(), owner.key().as_ref(), authority.key().as_ref()],
+        bump = journal_delegate.bump,
+    )]
+    pub journal_delegate: Option<Account<'info, JournalDelegate>>,
+
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(
+        mut,
+        constraint = JournalDelegate::authorizes(
+            journal_delegate.as_deref(),
+            &owner.key(),
+            &authority.key(),
+            DELEGATE_APPEND,
+        )? @ JournalError::NotAuthorized,
+    )]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _archive_entry(ctx: Context<ArchiveEntry>, grace_period: Option<u32>) -> Result<()> {
+    let clock = Clock::get()?;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.archived_at = Some(clock.unix_timestamp);
+    journal_entry.purge_after = grace_period.map(|seconds| clock.unix_timestamp + i64::from(seconds));
+
+    emit!(EntryArchived {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        purge_after: journal_entry.purge_after,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Soft-deletes an entry. With a `grace_period` in seconds, `purge_entry` is
+/// refused until it has passed.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct ArchiveEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    pub owner: Signer<'info>,
+}
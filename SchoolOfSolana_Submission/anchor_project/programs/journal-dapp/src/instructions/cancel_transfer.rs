@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _cancel_transfer(ctx: Context<CancelTransfer>) -> Result<()> {
+    emit!(TransferCancelled {
+        owner: ctx.accounts.owner.key(),
+        entry: ctx.accounts.transfer_offer.entry,
+        recipient: ctx.accounts.transfer_offer.recipient,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Closes the offer for `entry` at `transfer_offer` when one is open, for
+/// instructions that delete the entry. Its rent goes to `owner`.
+pub(crate) fn close_offer<'info>(
+    entry: Pubkey,
+    transfer_offer: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+) -> Result<()> {
+    let seeds = [TRANSFER_SEED.as_bytes(), entry.as_ref()];
+    if transfer_offer.key() != Pubkey::find_program_address(&seeds, &crate::ID).0 {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    if transfer_offer.owner != &crate::ID {
+        return Ok(());
+    }
+    let offer = TransferOffer::try_deserialize(&mut &transfer_offer.try_borrow_data()?[..])?;
+    emit!(TransferCancelled {
+        owner: offer.owner,
+        entry,
+        recipient: offer.recipient,
+        slot: Clock::get()?.slot,
+    });
+
+    let lamports = transfer_offer.lamports();
+    **transfer_offer.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? += lamports;
+    transfer_offer.assign(&system_program::ID);
+    transfer_offer.realloc(0, false)?;
+    Ok(())
+}
+
+/// Withdraws an open offer. Deleting, renaming or moving the entry closes
+/// the offer as well, since it is addressed by the entry.
+#[derive(Accounts)]
+#[instruction(entry: Pubkey)]
+pub struct CancelTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), entry.as_ref()],
+        bump = transfer_offer.bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub transfer_offer: Account<'info, TransferOffer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
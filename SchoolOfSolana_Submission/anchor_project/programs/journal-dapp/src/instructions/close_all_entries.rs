@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_children, close_offer};
+use crate::states::*;
+
+pub fn _close_all_entries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseAllEntries<'info>>,
+    entry_count: u8,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.to_account_info();
+    let owner_key = owner.key();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let slot = Clock::get()?.slot;
+
+    let mut rest = ctx.remaining_accounts;
+    let mut left_notebooks = Vec::new();
+    for _ in 0..entry_count {
+        let [info, transfer_offer, tail @ ..] = rest else {
+            return err!(JournalError::BatchAccountsMismatch);
+        };
+        // An entry passed twice fails here, as it is already closed.
+        let journal_entry = Account::<JournalEntry>::try_from(info)?;
+        let entry_seed = entry_seed(&journal_entry.title, journal_entry.notebook);
+        let seeds = [ENTRY_SEED.as_bytes(), entry_seed.as_ref(), owner_key.as_ref()];
+        if info.key() != Pubkey::find_program_address(&seeds, &crate::ID).0 {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        if journal_entry.feed_page.is_some() {
+            return err!(JournalError::EntryPublic);
+        }
+        if !ctx.accounts.journal_page.remove(&info.key()) {
+            return err!(JournalError::EntryNotIndexed);
+        }
+        ctx.accounts.user_journal.entry_count -= 1;
+        if journal_entry.notebook != 0 {
+            left_notebooks.push(journal_entry.notebook);
+        }
+
+        if tail.len() < journal_entry.child_count() {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        let (children, tail) = tail.split_at(journal_entry.child_count());
+        close_children(&journal_entry, children, &owner, &system_program)?;
+        close_offer(info.key(), transfer_offer, &owner)?;
+
+        emit!(EntryDeleted {
+            owner: owner_key,
+            entry: info.key(),
+            authority: owner_key,
+            title_hash: journal_entry.title_hash(),
+            slot,
+        });
+        journal_entry.close(owner.clone())?;
+        rest = tail;
+    }
+
+    for (i, info) in rest.iter().enumerate() {
+        // A second copy of the same account would overwrite the first on exit.
+        if rest[..i].iter().any(|other| other.key() == info.key()) {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        let mut notebook = Account::<Notebook>::try_from(info)?;
+        if notebook.owner != owner_key {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        let before = left_notebooks.len();
+        left_notebooks.retain(|id| *id != notebook.id);
+        notebook.entry_count -= (before - left_notebooks.len()) as u64;
+        notebook.exit(&crate::ID)?;
+    }
+    if !left_notebooks.is_empty() {
+        return err!(JournalError::NotebookRequired);
+    }
+
+    // The current page stays until `close_journal`, so new entries keep
+    // landing in the slot matching their id.
+    let journal_page = &ctx.accounts.journal_page;
+    let page_closed = journal_page.index != ctx.accounts.user_journal.current_page()
+        && journal_page.entries.iter().all(|entry| *entry == Pubkey::default());
+    if page_closed {
+        journal_page.close(owner.clone())?;
+    }
+
+    emit!(JournalSwept {
+        owner: owner_key,
+        page_index: journal_page.index,
+        closed: entry_count,
+        page_closed,
+        entries_left: ctx.accounts.user_journal.entry_count,
+        slot,
+    });
+    Ok(())
+}
+
+/// Closes up to `entry_count` of the entries listed on index page
+/// `page_index` together with everything hanging off them, and the page
+/// itself once it is empty. Meant to be called page by page until
+/// `JournalSwept::entries_left` reaches zero, then followed by
+/// `close_journal`.
+///
+/// `remaining_accounts` holds, for each entry, the entry, its `TransferOffer`
+/// address, closed when open, and the accounts `close_children` expects.
+/// The notebooks holding any of the entries come last, each once.
+/// Archived entries are closed regardless of their grace period, and sealed
+/// and notarized entries too: a seal guards the content against edits, not
+/// against its owner forgetting it. Public entries are refused until they
+/// are taken off the feed.
+#[derive(Accounts)]
+#[instruction(page_index: u64)]
+pub struct CloseAllEntries<'info> {
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump = user_journal.bump,
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(
+        mut,
+        seeds = [JOURNAL_PAGE_SEED.as_bytes(), owner.key().as_ref(), page_index.to_le_bytes().as_ref()],
+        bump = journal_page.bump,
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
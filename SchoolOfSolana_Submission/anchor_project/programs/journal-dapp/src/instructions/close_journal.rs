@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _close_journal<'info>(ctx: Context<'_, '_, 'info, 'info, CloseJournal<'info>>, page_count: u8) -> Result<()> {
+    let owner = ctx.accounts.owner.to_account_info();
+    let owner_key = owner.key();
+    let user_journal = &ctx.accounts.user_journal;
+    let first_page = user_journal.closed_pages;
+    let first_notebook = user_journal.closed_notebooks + 1;
+    if ctx.remaining_accounts.len() < page_count as usize {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    let (pages, notebooks) = ctx.remaining_accounts.split_at(page_count as usize);
+    let pages_end = first_page + pages.len() as u64;
+    let notebooks_end = user_journal.closed_notebooks as u64 + notebooks.len() as u64;
+    if pages_end > user_journal.page_count() || notebooks_end > user_journal.last_notebook_id as u64 {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    // New entries keep landing on the current page, so it stays open until
+    // the call that closes the journal itself.
+    let finishing = pages_end == user_journal.page_count() && notebooks_end == user_journal.last_notebook_id as u64;
+    let current_page = user_journal.current_page();
+
+    let mut closed_pages = first_page;
+    for (index, info) in (first_page..).zip(pages) {
+        if index == current_page && !finishing {
+            break;
+        }
+        let index_bytes = index.to_le_bytes();
+        let seeds = [JOURNAL_PAGE_SEED.as_bytes(), owner_key.as_ref(), index_bytes.as_ref()];
+        if !is_closed_pda(info, &seeds)? {
+            let journal_page = Account::<JournalPage>::try_from(info)?;
+            if journal_page.entries.iter().any(|entry| *entry != Pubkey::default()) {
+                return err!(JournalError::JournalNotEmpty);
+            }
+            journal_page.close(owner.clone())?;
+        }
+        closed_pages = index + 1;
+    }
+    for (id, info) in (first_notebook..).zip(notebooks) {
+        let id_bytes = id.to_le_bytes();
+        let seeds = [NOTEBOOK_SEED.as_bytes(), owner_key.as_ref(), id_bytes.as_ref()];
+        if !is_closed_pda(info, &seeds)? {
+            let notebook = Account::<Notebook>::try_from(info)?;
+            if notebook.entry_count > 0 {
+                return err!(JournalError::NotebookNotEmpty);
+            }
+            notebook.close(owner.clone())?;
+        }
+    }
+
+    if !finishing {
+        let user_journal = &mut ctx.accounts.user_journal;
+        user_journal.closed_pages = closed_pages;
+        user_journal.closed_notebooks += notebooks.len() as u32;
+        emit!(JournalClosing {
+            owner: owner_key,
+            closed_pages,
+            closed_notebooks: user_journal.closed_notebooks,
+            pages_left: user_journal.page_count() - closed_pages,
+            notebooks_left: user_journal.last_notebook_id - user_journal.closed_notebooks,
+            slot: Clock::get()?.slot,
+        });
+        return Ok(());
+    }
+    ctx.accounts.user_journal.close(owner.clone())?;
+    emit!(JournalClosed {
+        owner: owner_key,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Checks that `info` is the PDA of `seeds` and reports whether it no longer
+/// exists.
+fn is_closed_pda(info: &AccountInfo, seeds: &[&[u8]]) -> Result<bool> {
+    if info.key() != Pubkey::find_program_address(seeds, &crate::ID).0 {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    Ok(info.data_is_empty())
+}
+
+/// Closes the index pages and notebooks of a journal that holds no entries,
+/// a batch at a time, and the owner's `UserJournal` along with the last
+/// batch, so a later journal starts from a clean slate. `remaining_accounts`
+/// holds `page_count` consecutive index pages starting at
+/// `UserJournal::closed_pages` and then consecutive notebooks starting after
+/// `closed_notebooks`, closed ones included. The call that reaches both
+/// `UserJournal::page_count` and `last_notebook_id` closes the journal.
+#[derive(Accounts)]
+pub struct CloseJournal<'info> {
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump = user_journal.bump,
+        constraint = user_journal.entry_count == 0 @ JournalError::JournalNotEmpty,
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _close_notebook(ctx: Context<CloseNotebook>) -> Result<()> {
+    let notebook = &ctx.accounts.notebook;
+    emit!(NotebookClosed {
+        owner: notebook.owner,
+        notebook: notebook.key(),
+        id: notebook.id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Closes a notebook that no longer holds any entries. Its id is not reused.
+#[derive(Accounts)]
+#[instruction(notebook_id: u32)]
+pub struct CloseNotebook<'info> {
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), notebook_id.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+        constraint = notebook.entry_count == 0 @ JournalError::NotebookNotEmpty,
+        close = owner,
+    )]
+    pub notebook: Account<'info, Notebook>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::create_pda;
+use crate::states::*;
+
+pub fn _create_entries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateEntries<'info>>,
+    entries: Vec<NewEntry>,
+) -> Result<()> {
+    if entries.is_empty() {
+        return err!(JournalError::EmptyBatch);
+    }
+    let owner = ctx.accounts.owner.to_account_info();
+    let owner_key = owner.key();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let user_journal = &mut ctx.accounts.user_journal;
+    user_journal.owner = owner_key;
+    user_journal.bump = ctx.bumps.user_journal;
+
+    let first_page = user_journal.current_page();
+    let last_id = user_journal.next_entry_id + entries.len() as u64 - 1;
+    let page_count = (last_id / ENTRIES_PER_PAGE as u64 - first_page + 1) as usize;
+    if ctx.remaining_accounts.len() != entries.len() + page_count {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    let (entry_accounts, page_accounts) = ctx.remaining_accounts.split_at(entries.len());
+    let mut pages = Vec::with_capacity(page_count);
+    for (index, info) in (first_page..).zip(page_accounts) {
+        pages.push(open_page(info, owner_key, index, &owner, &system_program)?);
+    }
+
+    let clock = Clock::get()?;
+    for (input, info) in entries.into_iter().zip(entry_accounts) {
+        validate_title(&input.title)?;
+        validate_message(&input.message)?;
+        let title_seed = title_seed(&input.title);
+        let seeds = [ENTRY_SEED.as_bytes(), title_seed.as_ref(), owner_key.as_ref()];
+        let (address, bump) = Pubkey::find_program_address(&seeds, &crate::ID);
+        if info.key() != address {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+
+        // A title repeated within the batch fails here like an existing one.
+        let entry = JournalEntry::new(owner_key, input.title, input.message, clock.unix_timestamp);
+        create_pda(info, entry.current_space(), &[&seeds[..], &[&[bump]]].concat(), &owner, &system_program)?;
+        entry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        let page = &mut pages[(user_journal.current_page() - first_page) as usize];
+        user_journal.push_entry(page, address);
+
+        emit!(EntryCreated {
+            owner: owner_key,
+            entry: address,
+            title_hash: title_seed,
+            title_len: entry.title.len() as u16,
+            content_len: entry.content_len(),
+            encrypted: false,
+            slot: clock.slot,
+        });
+    }
+    for page in pages {
+        page.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Loads the owner's index page `index`, creating it if this batch is the
+/// first to fill it.
+fn open_page<'info>(
+    info: &'info AccountInfo<'info>,
+    owner: Pubkey,
+    index: u64,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Account<'info, JournalPage>> {
+    let index_bytes = index.to_le_bytes();
+    let seeds = [JOURNAL_PAGE_SEED.as_bytes(), owner.as_ref(), index_bytes.as_ref()];
+    let (address, bump) = Pubkey::find_program_address(&seeds, &crate::ID);
+    if info.key() != address {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    if info.owner == &system_program.key() && info.data_is_empty() {
+        let space = 8 + JournalPage::INIT_SPACE;
+        create_pda(info, space, &[&seeds[..], &[&[bump]]].concat(), payer, system_program)?;
+        let journal_page = JournalPage {
+            owner,
+            index,
+            entries: Vec::new(),
+            bump,
+        };
+        journal_page.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    Account::try_from(info)
+}
+
+/// Creates many entries outside any notebook in one go; if any of them
+/// fails, none is created. `remaining_accounts` holds the address of each
+/// entry, in the order of `entries`, followed by every index page from
+/// `UserJournal::current_page` to the one receiving the last entry.
+#[derive(Accounts)]
+pub struct CreateEntries<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
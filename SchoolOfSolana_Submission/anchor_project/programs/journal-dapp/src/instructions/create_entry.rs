@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{index_tags, join_notebook};
+use crate::states::*;
+
+pub fn _create_entry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateEntry<'info>>,
+    title: String,
+    message: String,
+    tags: Vec<String>,
+) -> Result<()> {
+    validate_title(&title)?;
+    validate_message(&message)?;
+    validate_tags(&tags)?;
+    if ctx.remaining_accounts.len() != tags.len() {
+        return err!(JournalError::MissingTagIndexes);
+    }
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let mut entry = JournalEntry::new(
+        ctx.accounts.owner.key(),
+        title,
+        message,
+        Clock::get()?.unix_timestamp,
+    );
+    entry.notebook = join_notebook(ctx.accounts.notebook.as_deref_mut());
+    let owner = ctx.accounts.owner.to_account_info();
+    index_tags(
+        journal_entry.key(),
+        owner.key(),
+        &tags,
+        ctx.remaining_accounts,
+        &owner,
+        &owner,
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    entry.tags = tags;
+    journal_entry.set_inner(entry);
+
+    init_index(
+        &mut ctx.accounts.user_journal,
+        &mut ctx.accounts.journal_page,
+        ctx.accounts.owner.key(),
+        ctx.bumps.user_journal,
+        ctx.bumps.journal_page,
+    );
+    ctx.accounts
+        .user_journal
+        .push_entry(&mut ctx.accounts.journal_page, journal_entry.key());
+
+    emit!(EntryCreated {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        title_len: journal_entry.title.len() as u16,
+        content_len: journal_entry.content_len(),
+        encrypted: false,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Fills in the index accounts; they may have just been created by
+/// `init_if_needed`, and the seeds pin every field written here.
+pub(crate) fn init_index(
+    user_journal: &mut UserJournal,
+    journal_page: &mut JournalPage,
+    owner: Pubkey,
+    user_journal_bump: u8,
+    journal_page_bump: u8,
+) {
+    user_journal.owner = owner;
+    user_journal.bump = user_journal_bump;
+    journal_page.owner = owner;
+    journal_page.index = user_journal.current_page();
+    journal_page.bump = journal_page_bump;
+}
+
+/// Creates an entry. `remaining_accounts` holds the `TagIndex` of each of
+/// `tags`, in the same order.
+#[derive(Accounts)]
+#[instruction(title: String, message: String, tags: Vec<String>)]
+pub struct CreateEntry<'info> {
+    #[account(
+        init,
+        seeds = [
+            ENTRY_SEED.as_bytes(),
+            entry_seed(&title, notebook.as_ref().map_or(0, |notebook| notebook.id)).as_ref(),
+            owner.key().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = JournalEntry::space(title.len(), message.len()) + JournalEntry::tags_space(&tags)
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// The owner's entry index, created with their first entry.
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    /// The index page that receives the new entry's id.
+    #[account(
+        init_if_needed,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            user_journal.current_page().to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = 8 + JournalPage::INIT_SPACE
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    /// Notebook receiving the entry; omitted for entries outside any notebook.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), notebook.id.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+    )]
+    pub notebook: Option<Account<'info, Notebook>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
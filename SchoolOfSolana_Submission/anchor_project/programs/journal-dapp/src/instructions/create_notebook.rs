@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+pub fn _create_notebook(ctx: Context<CreateNotebook>, name: String, description: String) -> Result<()> {
+    validate_notebook_name(&name)?;
+    validate_description(&description)?;
+
+    // The index may have just been created by `init_if_needed`.
+    let user_journal = &mut ctx.accounts.user_journal;
+    user_journal.owner = ctx.accounts.owner.key();
+    user_journal.bump = ctx.bumps.user_journal;
+    user_journal.last_notebook_id += 1;
+
+    let notebook = &mut ctx.accounts.notebook;
+    notebook.owner = ctx.accounts.owner.key();
+    notebook.id = user_journal.last_notebook_id;
+    notebook.name = name;
+    notebook.description = description;
+    notebook.entry_count = 0;
+    notebook.bump = ctx.bumps.notebook;
+
+    emit!(NotebookCreated {
+        owner: notebook.owner,
+        notebook: notebook.key(),
+        id: notebook.id,
+        name_len: notebook.name.len() as u16,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Creates the owner's next notebook. Its id is `last_notebook_id + 1`.
+#[derive(Accounts)]
+pub struct CreateNotebook<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(
+        init,
+        seeds = [
+            NOTEBOOK_SEED.as_bytes(),
+            owner.key().as_ref(),
+            (user_journal.last_notebook_id + 1).to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = 8 + Notebook::INIT_SPACE
+    )]
+    pub notebook: Account<'info, Notebook>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
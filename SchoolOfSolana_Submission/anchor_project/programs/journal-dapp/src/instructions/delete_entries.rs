@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_offer, resize_account};
+use crate::states::*;
+
+pub fn _delete_entries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeleteEntries<'info>>,
+    titles: Vec<String>,
+) -> Result<()> {
+    if titles.is_empty() {
+        return err!(JournalError::EmptyBatch);
+    }
+    if ctx.remaining_accounts.len() < 2 * titles.len() {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    let owner = ctx.accounts.owner.to_account_info();
+    let owner_key = owner.key();
+    let (entry_accounts, rest) = ctx.remaining_accounts.split_at(titles.len());
+    let (offer_accounts, index_accounts) = rest.split_at(titles.len());
+    let mut pages = Vec::new();
+    let mut notebooks = Vec::new();
+    let mut tag_indexes = Vec::new();
+    for (i, info) in index_accounts.iter().enumerate() {
+        // A second copy of the same account would overwrite the first on exit.
+        if index_accounts[..i].iter().any(|other| other.key() == info.key()) {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        if let Ok(journal_page) = Account::<JournalPage>::try_from(info) {
+            pages.push(journal_page);
+        } else if let Ok(tag_index) = Account::<TagIndex>::try_from(info) {
+            tag_indexes.push(tag_index);
+        } else {
+            notebooks.push(Account::<Notebook>::try_from(info)?);
+        }
+    }
+    if pages.iter().any(|page| page.owner != owner_key)
+        || notebooks.iter().any(|notebook| notebook.owner != owner_key)
+        || tag_indexes.iter().any(|tag_index| tag_index.owner != owner_key)
+    {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+
+    let slot = Clock::get()?.slot;
+    for ((title, info), transfer_offer) in titles.iter().zip(entry_accounts).zip(offer_accounts) {
+        // An entry passed twice fails here, as it is already closed.
+        let journal_entry = Account::<JournalEntry>::try_from(info)?;
+        let entry_seed = entry_seed(title, journal_entry.notebook);
+        let seeds = [ENTRY_SEED.as_bytes(), entry_seed.as_ref(), owner_key.as_ref()];
+        if info.key() != Pubkey::find_program_address(&seeds, &crate::ID).0 {
+            return err!(JournalError::BatchAccountsMismatch);
+        }
+        if journal_entry.archived_at.is_some() {
+            return err!(JournalError::EntryArchived);
+        }
+        if journal_entry.seal.is_some() {
+            return err!(JournalError::EntrySealed);
+        }
+        if journal_entry.feed_page.is_some() {
+            return err!(JournalError::EntryPublic);
+        }
+        if journal_entry.has_children() {
+            return err!(JournalError::EntryHasChildren);
+        }
+        for tag in &journal_entry.tags {
+            let tag_index = tag_indexes
+                .iter_mut()
+                .find(|tag_index| tag_index.tag == *tag)
+                .ok_or(JournalError::MissingTagIndexes)?;
+            if !tag_index.entries.contains(&info.key()) {
+                return err!(JournalError::TagIndexMismatch);
+            }
+            tag_index.entries.retain(|tagged| *tagged != info.key());
+        }
+
+        if !pages.iter_mut().any(|page| page.remove(&info.key())) {
+            return err!(JournalError::EntryNotIndexed);
+        }
+        ctx.accounts.user_journal.entry_count -= 1;
+        if journal_entry.notebook != 0 {
+            let notebook = notebooks
+                .iter_mut()
+                .find(|notebook| notebook.id == journal_entry.notebook)
+                .ok_or(JournalError::NotebookRequired)?;
+            notebook.entry_count -= 1;
+        }
+
+        close_offer(info.key(), transfer_offer, &owner)?;
+
+        emit!(EntryDeleted {
+            owner: owner_key,
+            entry: info.key(),
+            authority: owner_key,
+            title_hash: journal_entry.title_hash(),
+            slot,
+        });
+        journal_entry.close(owner.clone())?;
+    }
+    for page in pages {
+        page.exit(&crate::ID)?;
+    }
+    for notebook in notebooks {
+        notebook.exit(&crate::ID)?;
+    }
+    let system_program = ctx.accounts.system_program.to_account_info();
+    for tag_index in tag_indexes {
+        if tag_index.entries.is_empty() {
+            tag_index.close(owner.clone())?;
+        } else {
+            let space = TagIndex::space(tag_index.tag.len(), tag_index.entries.len());
+            resize_account(&tag_index.to_account_info(), space, &owner, &owner, &system_program)?;
+            tag_index.exit(&crate::ID)?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes many entries in one go; if any of them fails, none is deleted.
+/// `remaining_accounts` holds each entry, in the order of `titles`, then the
+/// `TransferOffer` address of each, in the same order, which is closed when
+/// open, and then the index pages listing them, the notebooks holding them and the
+/// `TagIndex` of each of their tags, each once and in any order. Entries
+/// with pages, reader grants or stored revisions have to go through
+/// `delete_entry`.
+#[derive(Accounts)]
+pub struct DeleteEntries<'info> {
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump = user_journal.bump,
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
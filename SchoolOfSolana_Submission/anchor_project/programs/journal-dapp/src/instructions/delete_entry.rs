@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_offer, leave_notebook, unindex_tags};
+use crate::states::*;
+
+pub fn _delete_entry<'info>(ctx: Context<'_, '_, 'info, 'info, DeleteEntry<'info>>) -> Result<()> {
+    let entry = ctx.accounts.journal_entry.key();
+    if !ctx.accounts.journal_page.remove(&entry) {
+        return err!(JournalError::EntryNotIndexed);
+    }
+    ctx.accounts.user_journal.entry_count -= 1;
+    leave_notebook(&ctx.accounts.journal_entry, ctx.accounts.notebook.as_deref_mut())?;
+
+    close_children(
+        &ctx.accounts.journal_entry,
+        ctx.remaining_accounts,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    close_offer(
+        entry,
+        &ctx.accounts.transfer_offer,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    emit!(EntryDeleted {
+        owner: ctx.accounts.owner.key(),
+        entry,
+        authority: ctx.accounts.authority.key(),
+        title_hash: ctx.accounts.journal_entry.title_hash(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Releases the accounts hanging off an entry: all of its `EntryPage`s and
+/// then all of its `EntryReader`s, each group in any order, are closed, the
+/// entry is taken off the `TagIndex` of each of its tags, passed next in the
+/// entry's tag order, and its stored `JournalRevision`s, passed last in any
+/// order, are closed. Every one of them must be passed so that none outlives
+/// the entry, and none collides with a later entry at the same address.
+pub(crate) fn close_children<'info>(
+    journal_entry: &Account<'info, JournalEntry>,
+    children: &'info [AccountInfo<'info>],
+    destination: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if children.len() != journal_entry.child_count() {
+        return err!(JournalError::MissingChildAccounts);
+    }
+    let (pages, rest) = children.split_at(journal_entry.pages.len());
+    let (readers, rest) = rest.split_at(journal_entry.reader_count as usize);
+    let (tag_indexes, revisions) = rest.split_at(journal_entry.tags.len());
+    for info in pages {
+        // A page passed twice fails here, as it is already closed.
+        let entry_page = Account::<EntryPage>::try_from(info)?;
+        if entry_page.entry != journal_entry.key() || !journal_entry.pages.contains(&entry_page.page_id) {
+            return err!(JournalError::PageNotFound);
+        }
+        entry_page.close(destination.clone())?;
+    }
+    for info in readers {
+        let entry_reader = Account::<EntryReader>::try_from(info)?;
+        if entry_reader.entry != journal_entry.key() {
+            return err!(JournalError::ReaderNotFound);
+        }
+        entry_reader.close(destination.clone())?;
+    }
+    unindex_tags(
+        journal_entry.key(),
+        destination,
+        &journal_entry.tags,
+        tag_indexes,
+        system_program,
+    )?;
+    for info in revisions {
+        let journal_revision = Account::<JournalRevision>::try_from(info)?;
+        if journal_revision.entry != journal_entry.key() {
+            return err!(JournalError::RevisionNotFound);
+        }
+        journal_revision.close(destination.clone())?;
+    }
+    Ok(())
+}
+
+/// Deletes an entry together with its pages, reader grants and stored
+/// revisions, which go in `remaining_accounts` as described on
+/// `close_children`, and its open transfer offer, if any. Rent always
+/// returns to the owner, also when a delegate with `DELEGATE_DELETE` signs.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct DeleteEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+        close = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump = user_journal.bump,
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    /// CHECK: the entry's `TransferOffer`, closed along with it when open
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump,
+    )]
+    pub transfer_offer: UncheckedAccount<'info>,
+
+    /// The index page listing the entry being deleted.
+    #[account(
+        mut,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            journal_page.index.to_le_bytes().as_ref()
+        ],
+        bump = journal_page.bump,
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    /// The entry's notebook; omitted for entries outside any notebook.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), journal_entry.notebook.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+    )]
+    pub notebook: Option<Account<'info, Notebook>>,
+
+    /// Grant held by `authority`; omitted when the owner signs.
+    #[account(
+        seeds = [DELEGATE_SEED.as_bytes(), owner.key().as_ref(), authority.key().as_ref()],
+        bump = journal_delegate.bump,
+    )]
+    pub journal_delegate: Option<Account<'info, JournalDelegate>>,
+
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(
+        constraint = JournalDelegate::authorizes(
+            journal_delegate.as_deref(),
+            &owner.key(),
+            &authority.key(),
+            DELEGATE_DELETE,
+        )? @ JournalError::NotAuthorized,
+    )]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _grant_reader(
+    ctx: Context<GrantReader>,
+    reader: Pubkey,
+    wrapped_key: [u8; WRAPPED_KEY_LENGTH],
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let entry_reader = &mut ctx.accounts.entry_reader;
+    // An existing grant only gets the new wrapped key.
+    if entry_reader.entry == Pubkey::default() {
+        journal_entry.reader_count += 1;
+    }
+    entry_reader.entry = journal_entry.key();
+    entry_reader.reader = reader;
+    entry_reader.wrapped_key = wrapped_key;
+    let clock = Clock::get()?;
+    entry_reader.granted_at = clock.unix_timestamp;
+    entry_reader.bump = ctx.bumps.entry_reader;
+
+    emit!(ReaderGranted {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        reader,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Shares an envelope-encrypted entry with `reader`, or replaces the wrapped
+/// key of a reader who already has a grant, as after the content key is
+/// rotated. The content key is wrapped client-side, so the program never
+/// sees it.
+#[derive(Accounts)]
+#[instruction(title: String, reader: Pubkey)]
+pub struct GrantReader<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry
+            .encrypted
+            .as_ref()
+            .is_some_and(|content| content.scheme == EncryptionScheme::XChaCha20Poly1305Envelope)
+            @ JournalError::EntryNotShareable,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init_if_needed,
+        seeds = [READER_SEED.as_bytes(), journal_entry.key().as_ref(), reader.as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + EntryReader::INIT_SPACE
+    )]
+    pub entry_reader: Account<'info, EntryReader>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::init_index;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _migrate_entry(ctx: Context<MigrateEntry>, title: String) -> Result<()> {
+    // Legacy seeds took any bytes, but the entry must hold a valid title.
+    validate_title(&title)?;
+
+    let legacy_entry = &ctx.accounts.legacy_entry;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    // The original creation time was never recorded.
+    journal_entry.set_inner(JournalEntry::new(
+        legacy_entry.owner,
+        title,
+        legacy_entry.message.clone(),
+        Clock::get()?.unix_timestamp,
+    ));
+
+    init_index(
+        &mut ctx.accounts.user_journal,
+        &mut ctx.accounts.journal_page,
+        ctx.accounts.owner.key(),
+        ctx.bumps.user_journal,
+        ctx.bumps.journal_page,
+    );
+    ctx.accounts
+        .user_journal
+        .push_entry(&mut ctx.accounts.journal_page, journal_entry.key());
+
+    emit!(EntryMigrated {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        legacy_entry: legacy_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        content_len: journal_entry.content_len(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Moves an entry created under the original `[title, owner]` seeds to its
+/// hash-based address. The legacy account is closed and its rent returned.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct MigrateEntry<'info> {
+    #[account(
+        mut,
+        seeds = [title.as_bytes(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub legacy_entry: Account<'info, LegacyJournalEntry>,
+
+    #[account(
+        init,
+        seeds = [ENTRY_SEED.as_bytes(), title_seed(&title).as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = JournalEntry::space(title.len(), legacy_entry.message.len())
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            user_journal.current_page().to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = 8 + JournalPage::INIT_SPACE
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
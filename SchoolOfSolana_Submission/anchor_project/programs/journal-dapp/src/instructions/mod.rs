@@ -0,0 +1,113 @@
+pub use create_entry::*;
+pub mod create_entry;
+
+pub use update_entry::*;
+pub mod update_entry;
+
+pub use delete_entry::*;
+pub mod delete_entry;
+
+pub use migrate_entry::*;
+pub mod migrate_entry;
+
+pub use upgrade_entry::*;
+pub mod upgrade_entry;
+
+pub use set_history_mode::*;
+pub mod set_history_mode;
+
+pub use read_revision::*;
+pub mod read_revision;
+
+pub use restore_revision::*;
+pub mod restore_revision;
+
+pub use prune_revision::*;
+pub mod prune_revision;
+
+pub use append_page::*;
+pub mod append_page;
+
+pub use replace_page::*;
+pub mod replace_page;
+
+pub use remove_page::*;
+pub mod remove_page;
+
+pub use create_encrypted_entry::*;
+pub mod create_encrypted_entry;
+
+pub use update_encrypted_entry::*;
+pub mod update_encrypted_entry;
+
+pub use grant_reader::*;
+pub mod grant_reader;
+
+pub use revoke_reader::*;
+pub mod revoke_reader;
+
+pub use set_delegate::*;
+pub mod set_delegate;
+
+pub use revoke_delegate::*;
+pub mod revoke_delegate;
+
+pub use rename_entry::*;
+pub mod rename_entry;
+
+pub use archive_entry::*;
+pub mod archive_entry;
+
+pub use restore_entry::*;
+pub mod restore_entry;
+
+pub use purge_entry::*;
+pub mod purge_entry;
+
+pub use set_visibility::*;
+pub mod set_visibility;
+
+pub use create_notebook::*;
+pub mod create_notebook;
+
+pub use rename_notebook::*;
+pub mod rename_notebook;
+
+pub use close_notebook::*;
+pub mod close_notebook;
+
+pub use move_entry::*;
+pub mod move_entry;
+
+pub use append_to_entry::*;
+pub mod append_to_entry;
+
+pub use patch_entry::*;
+pub mod patch_entry;
+
+pub use seal_entry::*;
+pub mod seal_entry;
+
+pub use notarize::*;
+pub mod notarize;
+
+pub use offer_transfer::*;
+pub mod offer_transfer;
+
+pub use accept_transfer::*;
+pub mod accept_transfer;
+
+pub use cancel_transfer::*;
+pub mod cancel_transfer;
+
+pub use create_entries::*;
+pub mod create_entries;
+
+pub use delete_entries::*;
+pub mod delete_entries;
+
+pub use close_all_entries::*;
+pub mod close_all_entries;
+
+pub use close_journal::*;
+pub mod close_journal;
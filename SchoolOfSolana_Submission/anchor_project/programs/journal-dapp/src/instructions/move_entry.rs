@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_offer, relocate_entry};
+use crate::states::*;
+
+pub fn _move_entry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MoveEntry<'info>>,
+    title: String,
+    notebook_id: u32,
+) -> Result<()> {
+    let old_entry = ctx.accounts.journal_entry.key();
+    let from_notebook = ctx.accounts.journal_entry.notebook;
+    leave_notebook(&ctx.accounts.journal_entry, ctx.accounts.from_notebook.as_deref_mut())?;
+    if join_notebook(ctx.accounts.to_notebook.as_deref_mut()) != notebook_id {
+        return err!(JournalError::NotebookRequired);
+    }
+
+    let owner = ctx.accounts.owner.to_account_info();
+    let owner_key = owner.key();
+    let entry_seed = entry_seed(&title, notebook_id);
+    relocate_entry(
+        &mut ctx.accounts.journal_entry,
+        &ctx.accounts.new_entry,
+        &[
+            ENTRY_SEED.as_bytes(),
+            entry_seed.as_ref(),
+            owner_key.as_ref(),
+            &[ctx.bumps.new_entry],
+        ],
+        &mut ctx.accounts.journal_page,
+        ctx.remaining_accounts,
+        &owner,
+        &ctx.accounts.system_program.to_account_info(),
+        |journal_entry| journal_entry.notebook = notebook_id,
+    )?;
+    close_offer(old_entry, &ctx.accounts.transfer_offer, &owner)?;
+
+    emit!(EntryMoved {
+        owner: owner_key,
+        old_entry,
+        entry: ctx.accounts.new_entry.key(),
+        title_hash: title_seed(&title),
+        from_notebook,
+        to_notebook: notebook_id,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Counts a new entry in `notebook`, returning the id the entry should store.
+pub(crate) fn join_notebook(notebook: Option<&mut Notebook>) -> u32 {
+    notebook.map_or(0, |notebook| {
+        notebook.entry_count += 1;
+        notebook.id
+    })
+}
+
+/// Takes an entry out of its notebook's count. The notebook account has to
+/// be passed whenever the entry is in one.
+pub(crate) fn leave_notebook(journal_entry: &JournalEntry, notebook: Option<&mut Notebook>) -> Result<()> {
+    match (journal_entry.notebook, notebook) {
+        (0, _) => Ok(()),
+        (_, Some(notebook)) => {
+            notebook.entry_count -= 1;
+            Ok(())
+        }
+        (_, None) => err!(JournalError::NotebookRequired),
+    }
+}
+
+/// Moves an entry into notebook `notebook_id`, or out of any notebook with 0.
+/// The entry gets the address of its title in the target namespace, so the
+/// same restrictions and `remaining_accounts` as for `rename_entry` apply.
+#[derive(Accounts)]
+#[instruction(title: String, notebook_id: u32)]
+pub struct MoveEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+        constraint = journal_entry.notebook != notebook_id @ JournalError::SameNotebook,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// CHECK: address of the moved entry, created in the handler
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, notebook_id).as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub new_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the entry's `TransferOffer`, closed when open
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump,
+    )]
+    pub transfer_offer: UncheckedAccount<'info>,
+
+    /// The entry's current notebook; omitted when it is in none.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), journal_entry.notebook.to_le_bytes().as_ref()],
+        bump = from_notebook.bump,
+    )]
+    pub from_notebook: Option<Account<'info, Notebook>>,
+
+    /// The target notebook; omitted when moving the entry out of notebooks.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), notebook_id.to_le_bytes().as_ref()],
+        bump = to_notebook.bump,
+    )]
+    pub to_notebook: Option<Account<'info, Notebook>>,
+
+    /// The index page listing the entry being moved.
+    #[account(
+        mut,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            journal_page.index.to_le_bytes().as_ref()
+        ],
+        bump = journal_page.bump,
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::instructions::{init_index, join_notebook};
+use crate::states::*;
+
+pub fn _notarize(
+    ctx: Context<Notarize>,
+    title: String,
+    sha256: [u8; 32],
+    uri: Option<String>,
+    mime: Option<String>,
+) -> Result<()> {
+    validate_title(&title)?;
+    validate_notarization(uri.as_deref(), mime.as_deref())?;
+
+    let clock = Clock::get()?;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let mut entry = JournalEntry::new(ctx.accounts.owner.key(), title, String::new(), clock.unix_timestamp);
+    entry.notarization = Some(Notarization { sha256, uri, mime });
+    entry.seal = Some(Seal {
+        slot: clock.slot,
+        sealed_at: clock.unix_timestamp,
+        content_hash: sha256,
+    });
+    entry.notebook = join_notebook(ctx.accounts.notebook.as_deref_mut());
+    journal_entry.set_inner(entry);
+
+    init_index(
+        &mut ctx.accounts.user_journal,
+        &mut ctx.accounts.journal_page,
+        ctx.accounts.owner.key(),
+        ctx.bumps.user_journal,
+        ctx.bumps.journal_page,
+    );
+    ctx.accounts
+        .user_journal
+        .push_entry(&mut ctx.accounts.journal_page, journal_entry.key());
+
+    emit!(EntryCreated {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        title_len: journal_entry.title.len() as u16,
+        content_len: journal_entry.content_len(),
+        encrypted: false,
+        slot: clock.slot,
+    });
+    emit!(EntryNotarized {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        sha256,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Creates an entry attesting to a document by its SHA-256 instead of
+/// holding a message. The entry is sealed on creation, so its seal records
+/// when the hash was put on-chain.
+#[derive(Accounts)]
+#[instruction(title: String, sha256: [u8; 32], uri: Option<String>, mime: Option<String>)]
+pub struct Notarize<'info> {
+    #[account(
+        init,
+        seeds = [
+            ENTRY_SEED.as_bytes(),
+            entry_seed(&title, notebook.as_ref().map_or(0, |notebook| notebook.id)).as_ref(),
+            owner.key().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = JournalEntry::notarized_space(title.len(), uri.as_deref(), mime.as_deref())
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init_if_needed,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserJournal::INIT_SPACE
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            user_journal.current_page().to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = 8 + JournalPage::INIT_SPACE
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    /// Notebook receiving the entry; omitted for entries outside any notebook.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), notebook.id.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+    )]
+    pub notebook: Option<Account<'info, Notebook>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
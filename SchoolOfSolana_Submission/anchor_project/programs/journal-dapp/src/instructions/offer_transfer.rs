@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _offer_transfer(ctx: Context<OfferTransfer>, recipient: Pubkey) -> Result<()> {
+    if recipient == ctx.accounts.owner.key() {
+        return err!(JournalError::InvalidRecipient);
+    }
+
+    let clock = Clock::get()?;
+    let transfer_offer = &mut ctx.accounts.transfer_offer;
+    transfer_offer.entry = ctx.accounts.journal_entry.key();
+    transfer_offer.owner = ctx.accounts.owner.key();
+    transfer_offer.recipient = recipient;
+    transfer_offer.offered_at = clock.unix_timestamp;
+    transfer_offer.entry_created_at = ctx.accounts.journal_entry.created_at;
+    transfer_offer.bump = ctx.bumps.transfer_offer;
+
+    emit!(TransferOffered {
+        owner: transfer_offer.owner,
+        entry: transfer_offer.entry,
+        title_hash: ctx.accounts.journal_entry.title_hash(),
+        recipient,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Offers an entry to `recipient`, who takes it over with `accept_transfer`.
+/// By the time the offer is accepted, the entry must have no pages, reader
+/// grants or stored revisions, which stay with the old owner's address.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct OfferTransfer<'info> {
+    #[account(
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + TransferOffer::INIT_SPACE
+    )]
+    pub transfer_offer: Account<'info, TransferOffer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _prune_revision(ctx: Context<PruneRevision>) -> Result<()> {
+    // The entry's `history_hash` still commits to the pruned revision, so the
+    // gap stays detectable.
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.history_len -= 1;
+
+    emit!(RevisionPruned {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        revision: ctx.accounts.journal_revision.revision,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, revision: u32)]
+pub struct PruneRevision<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            revision.to_le_bytes().as_ref()
+        ],
+        bump = journal_revision.bump,
+        close = owner,
+    )]
+    pub journal_revision: Account<'info, JournalRevision>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
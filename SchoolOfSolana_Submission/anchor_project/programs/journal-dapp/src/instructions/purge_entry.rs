@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_children, close_offer, leave_notebook};
+use crate::states::*;
+
+pub fn _purge_entry<'info>(ctx: Context<'_, '_, 'info, 'info, PurgeEntry<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    let journal_entry = &ctx.accounts.journal_entry;
+    if journal_entry
+        .purge_after
+        .is_some_and(|purge_after| clock.unix_timestamp < purge_after)
+    {
+        return err!(JournalError::GracePeriodActive);
+    }
+
+    let entry = journal_entry.key();
+    if !ctx.accounts.journal_page.remove(&entry) {
+        return err!(JournalError::EntryNotIndexed);
+    }
+    ctx.accounts.user_journal.entry_count -= 1;
+    leave_notebook(&ctx.accounts.journal_entry, ctx.accounts.notebook.as_deref_mut())?;
+
+    close_children(
+        journal_entry,
+        ctx.remaining_accounts,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    close_offer(
+        entry,
+        &ctx.accounts.transfer_offer,
+        &ctx.accounts.owner.to_account_info(),
+    )?;
+
+    emit!(EntryPurged {
+        owner: ctx.accounts.owner.key(),
+        entry,
+        title_hash: journal_entry.title_hash(),
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Permanently deletes an archived entry, exactly like `delete_entry` does
+/// for an active one.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct PurgeEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.archived_at.is_some() @ JournalError::EntryNotArchived,
+        close = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [USER_JOURNAL_SEED.as_bytes(), owner.key().as_ref()],
+        bump = user_journal.bump,
+    )]
+    pub user_journal: Account<'info, UserJournal>,
+
+    /// CHECK: the entry's `TransferOffer`, closed along with it when open
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump,
+    )]
+    pub transfer_offer: UncheckedAccount<'info>,
+
+    /// The index page listing the entry being purged.
+    #[account(
+        mut,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            journal_page.index.to_le_bytes().as_ref()
+        ],
+        bump = journal_page.bump,
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    /// The entry's notebook; omitted for entries outside any notebook.
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), journal_entry.notebook.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+    )]
+    pub notebook: Option<Account<'info, Notebook>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::states::*;
+
+pub fn _read_revision(ctx: Context<ReadRevision>) -> Result<String> {
+    Ok(ctx.accounts.journal_revision.message.clone())
+}
+
+/// Returns a recorded message through the transaction return data, meant to
+/// be simulated. Anyone may read; the owner does not sign.
+#[derive(Accounts)]
+#[instruction(title: String, revision: u32)]
+pub struct ReadRevision<'info> {
+    #[account(
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            revision.to_le_bytes().as_ref()
+        ],
+        bump = journal_revision.bump,
+    )]
+    pub journal_revision: Account<'info, JournalRevision>,
+
+    pub owner: SystemAccount<'info>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _remove_page(ctx: Context<RemovePage>, page_id: u16) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.pages.retain(|id| *id != page_id);
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(PageRemoved {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        page_id,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, page_id: u16)]
+pub struct RemovePage<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        realloc = journal_entry.current_space() - 2,
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [
+            ENTRY_PAGE_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            page_id.to_le_bytes().as_ref()
+        ],
+        bump = entry_page.bump,
+        close = owner,
+    )]
+    pub entry_page: Account<'info, EntryPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
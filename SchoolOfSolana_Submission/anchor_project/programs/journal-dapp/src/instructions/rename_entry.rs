@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::{close_offer, resize_account, retag_entry};
+use crate::states::*;
+
+pub fn _rename_entry<'info>(ctx: Context<'_, '_, 'info, 'info, RenameEntry<'info>>, new_title: String) -> Result<()> {
+    validate_title(&new_title)?;
+
+    let clock = Clock::get()?;
+    let old_entry = ctx.accounts.journal_entry.key();
+    let old_title_hash = ctx.accounts.journal_entry.title_hash();
+    let title_hash = title_seed(&new_title);
+    let title_len = new_title.len() as u16;
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    if ctx.accounts.new_entry.key() == old_entry {
+        // Only letter case or whitespace changed, so the address stays.
+        resize_account(
+            &ctx.accounts.journal_entry.to_account_info(),
+            ctx.accounts.journal_entry.space_with_title(new_title.len()),
+            &owner,
+            &owner,
+            &system_program,
+        )?;
+        let journal_entry = &mut ctx.accounts.journal_entry;
+        journal_entry.title = new_title;
+        journal_entry.updated_at = clock.unix_timestamp;
+    } else {
+        let entry_seed = entry_seed(&new_title, ctx.accounts.journal_entry.notebook);
+        let owner_key = owner.key();
+        relocate_entry(
+            &mut ctx.accounts.journal_entry,
+            &ctx.accounts.new_entry,
+            &[
+                ENTRY_SEED.as_bytes(),
+                entry_seed.as_ref(),
+                owner_key.as_ref(),
+                &[ctx.bumps.new_entry],
+            ],
+            &mut ctx.accounts.journal_page,
+            ctx.remaining_accounts,
+            &owner,
+            &system_program,
+            |journal_entry| {
+                journal_entry.title = new_title;
+                journal_entry.updated_at = clock.unix_timestamp;
+            },
+        )?;
+        close_offer(old_entry, &ctx.accounts.transfer_offer, &owner)?;
+    }
+
+    emit!(EntryRenamed {
+        owner: owner.key(),
+        old_entry,
+        entry: ctx.accounts.new_entry.key(),
+        old_title_hash,
+        title_hash,
+        title_len,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Rewrites `journal_entry`, with `update` applied, into the PDA `new_entry`
+/// derived from `seeds`, closes the old account and repoints its slot on
+/// `journal_page`. `accounts` holds the `TagIndex` of each of its tags, in
+/// its order, which are pointed at the new address, followed by the
+/// children that `move_children` carries over.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn relocate_entry<'info>(
+    journal_entry: &mut Account<'info, JournalEntry>,
+    new_entry: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    journal_page: &mut JournalPage,
+    accounts: &'info [AccountInfo<'info>],
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    update: impl FnOnce(&mut JournalEntry),
+) -> Result<()> {
+    if !journal_page.replace(&journal_entry.key(), new_entry.key()) {
+        return err!(JournalError::EntryNotIndexed);
+    }
+    if accounts.len() < journal_entry.tags.len() {
+        return err!(JournalError::MissingChildAccounts);
+    }
+    let (tag_indexes, children) = accounts.split_at(journal_entry.tags.len());
+    retag_entry(
+        journal_entry.key(),
+        new_entry.key(),
+        owner.key(),
+        &journal_entry.tags,
+        tag_indexes,
+    )?;
+
+    let mut relocated = journal_entry.clone().into_inner();
+    update(&mut relocated);
+    write_entry(relocated, new_entry, seeds, owner, system_program)?;
+    move_children(journal_entry, new_entry.key(), children, owner, system_program)?;
+    journal_entry.close(owner.clone())
+}
+
+/// Recreates the pages, reader grants and stored revisions of
+/// `journal_entry` under `new_entry` and closes the old accounts, the owner
+/// paying for the new ones and getting the old rent back. `children` holds
+/// an old and a new address per child: first the pages, then the reader
+/// grants, then the revisions, each group in any order.
+fn move_children<'info>(
+    journal_entry: &Account<'info, JournalEntry>,
+    new_entry: Pubkey,
+    children: &'info [AccountInfo<'info>],
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (page_count, reader_count) = (journal_entry.pages.len(), journal_entry.reader_count as usize);
+    if children.len() != 2 * (page_count + reader_count + journal_entry.history_len as usize) {
+        return err!(JournalError::MissingChildAccounts);
+    }
+    let (pages, rest) = children.split_at(2 * page_count);
+    let (readers, revisions) = rest.split_at(2 * reader_count);
+    for pair in pages.chunks(2) {
+        // A page passed twice fails here, as it is already closed.
+        let entry_page = Account::<EntryPage>::try_from(&pair[0])?;
+        if entry_page.entry != journal_entry.key() || !journal_entry.pages.contains(&entry_page.page_id) {
+            return err!(JournalError::PageNotFound);
+        }
+        let page_id = entry_page.page_id.to_le_bytes();
+        let seeds = [ENTRY_PAGE_SEED.as_bytes(), new_entry.as_ref(), page_id.as_ref()];
+        move_child(entry_page, &pair[1], &seeds, owner, system_program, |entry_page, bump| {
+            entry_page.entry = new_entry;
+            entry_page.bump = bump;
+        })?;
+    }
+    for pair in readers.chunks(2) {
+        let entry_reader = Account::<EntryReader>::try_from(&pair[0])?;
+        if entry_reader.entry != journal_entry.key() {
+            return err!(JournalError::ReaderNotFound);
+        }
+        let reader = entry_reader.reader;
+        let seeds = [READER_SEED.as_bytes(), new_entry.as_ref(), reader.as_ref()];
+        move_child(entry_reader, &pair[1], &seeds, owner, system_program, |entry_reader, bump| {
+            entry_reader.entry = new_entry;
+            entry_reader.bump = bump;
+        })?;
+    }
+    for pair in revisions.chunks(2) {
+        let journal_revision = Account::<JournalRevision>::try_from(&pair[0])?;
+        if journal_revision.entry != journal_entry.key() {
+            return err!(JournalError::RevisionNotFound);
+        }
+        let revision = journal_revision.revision.to_le_bytes();
+        let seeds = [REVISION_SEED.as_bytes(), new_entry.as_ref(), revision.as_ref()];
+        move_child(journal_revision, &pair[1], &seeds, owner, system_program, |journal_revision, bump| {
+            journal_revision.entry = new_entry;
+            journal_revision.bump = bump;
+        })?;
+    }
+    Ok(())
+}
+
+/// Copies `child`, with `update` applied, into `new`, which must be the PDA
+/// of `seeds`, and closes `child`.
+fn move_child<'info, T: AccountSerialize + AccountDeserialize + Owner + Clone>(
+    child: Account<'info, T>,
+    new: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    update: impl FnOnce(&mut T, u8),
+) -> Result<()> {
+    let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    if new.key() != address {
+        return err!(JournalError::BatchAccountsMismatch);
+    }
+    let mut moved = child.clone().into_inner();
+    update(&mut moved, bump);
+    let bump = [bump];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+    create_pda(new, child.to_account_info().data_len(), &signer_seeds, owner, system_program)?;
+    moved.try_serialize(&mut &mut new.try_borrow_mut_data()?[..])?;
+    child.close(owner.clone())
+}
+
+/// Writes `relocated` into the PDA `new_entry` derived from `seeds` and
+/// closes `journal_entry`, sending its rent to `refund`. Accounts seeded by
+/// the old address would be orphaned, so entries that still have any are
+/// refused.
+pub(crate) fn rewrite_entry<'info>(
+    journal_entry: &mut Account<'info, JournalEntry>,
+    relocated: JournalEntry,
+    new_entry: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    payer: &AccountInfo<'info>,
+    refund: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if journal_entry.has_children() {
+        return err!(JournalError::EntryHasChildren);
+    }
+    write_entry(relocated, new_entry, seeds, payer, system_program)?;
+    journal_entry.close(refund.clone())
+}
+
+/// Creates the PDA `new_entry` derived from `seeds` and writes `relocated`
+/// into it.
+fn write_entry<'info>(
+    relocated: JournalEntry,
+    new_entry: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    create_pda(new_entry, relocated.current_space(), seeds, payer, system_program)?;
+    relocated.try_serialize(&mut &mut new_entry.try_borrow_mut_data()?[..])
+}
+
+/// Creates a program-owned PDA of `space` bytes, like Anchor's `init`. An
+/// address that was sent lamports ahead of time is topped up rather than
+/// rejected, so nobody can block a title by funding its address.
+pub(crate) fn create_pda<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.owner != &system_program::ID || !account.data_is_empty() {
+        return err!(JournalError::TitleTaken);
+    }
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds = &[seeds];
+    if account.lamports() == 0 {
+        let cpi_accounts = system_program::CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_context = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+        return system_program::create_account(cpi_context, rent, space as u64, &crate::ID);
+    }
+
+    let missing = rent.saturating_sub(account.lamports());
+    if missing > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), missing)?;
+    }
+    let cpi_accounts = system_program::Allocate {
+        account_to_allocate: account.clone(),
+    };
+    let cpi_context = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+    system_program::allocate(cpi_context, space as u64)?;
+    let cpi_accounts = system_program::Assign {
+        account_to_assign: account.clone(),
+    };
+    let cpi_context = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+    system_program::assign(cpi_context, &crate::ID)
+}
+
+/// Moves an entry to the address of `new_title`, keeping its timestamps,
+/// revision counter and history hash; a change in letter case only updates
+/// the title in place. `remaining_accounts` holds the `TagIndex` of each of
+/// the entry's tags, in its order, then an old and a new address for each
+/// page, reader grant and stored revision, which are addressed by the entry
+/// and move with it; see `move_children`. An open transfer offer names the
+/// old address, so it is closed.
+#[derive(Accounts)]
+#[instruction(old_title: String, new_title: String)]
+pub struct RenameEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&old_title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.feed_page.is_none() @ JournalError::EntryPublic,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// CHECK: address of the renamed entry, created in the handler
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&new_title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub new_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the entry's `TransferOffer`, closed when open
+    #[account(
+        mut,
+        seeds = [TRANSFER_SEED.as_bytes(), journal_entry.key().as_ref()],
+        bump,
+    )]
+    pub transfer_offer: UncheckedAccount<'info>,
+
+    /// The index page listing the entry being renamed.
+    #[account(
+        mut,
+        seeds = [
+            JOURNAL_PAGE_SEED.as_bytes(),
+            owner.key().as_ref(),
+            journal_page.index.to_le_bytes().as_ref()
+        ],
+        bump = journal_page.bump,
+    )]
+    pub journal_page: Account<'info, JournalPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
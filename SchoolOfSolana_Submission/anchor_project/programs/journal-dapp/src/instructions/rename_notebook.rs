@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+pub fn _rename_notebook(ctx: Context<RenameNotebook>, name: String) -> Result<()> {
+    validate_notebook_name(&name)?;
+
+    let notebook = &mut ctx.accounts.notebook;
+    notebook.name = name;
+
+    emit!(NotebookRenamed {
+        owner: notebook.owner,
+        notebook: notebook.key(),
+        id: notebook.id,
+        name_len: notebook.name.len() as u16,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Renames a notebook. Entry addresses depend on the notebook id only, so
+/// they are unaffected.
+#[derive(Accounts)]
+#[instruction(notebook_id: u32)]
+pub struct RenameNotebook<'info> {
+    #[account(
+        mut,
+        seeds = [NOTEBOOK_SEED.as_bytes(), owner.key().as_ref(), notebook_id.to_le_bytes().as_ref()],
+        bump = notebook.bump,
+    )]
+    pub notebook: Account<'info, Notebook>,
+
+    pub owner: Signer<'info>,
+}
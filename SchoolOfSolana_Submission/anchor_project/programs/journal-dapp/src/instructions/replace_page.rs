@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _replace_page(ctx: Context<ReplacePage>, content: String) -> Result<()> {
+    validate_page(&content)?;
+
+    let clock = Clock::get()?;
+    let entry_page = &mut ctx.accounts.entry_page;
+    entry_page.content = content;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(PageReplaced {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        page_id: entry_page.page_id,
+        page_len: entry_page.content.len() as u32,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, page_id: u16, content: String)]
+pub struct ReplacePage<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [
+            ENTRY_PAGE_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            page_id.to_le_bytes().as_ref()
+        ],
+        bump = entry_page.bump,
+        realloc = EntryPage::space(content.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub entry_page: Account<'info, EntryPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
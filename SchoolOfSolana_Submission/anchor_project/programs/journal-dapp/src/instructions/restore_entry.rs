@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _restore_entry(ctx: Context<RestoreEntry>) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.archived_at = None;
+    journal_entry.purge_after = None;
+
+    emit!(EntryRestored {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Brings an archived entry back into listings.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct RestoreEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.archived_at.is_some() @ JournalError::EntryNotArchived,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    pub owner: Signer<'info>,
+}
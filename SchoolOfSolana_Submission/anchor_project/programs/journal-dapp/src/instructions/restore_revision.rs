@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::snapshot_message;
+use crate::states::*;
+
+pub fn _restore_revision(ctx: Context<RestoreRevision>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let message = ctx.accounts.restored_revision.message.clone();
+    snapshot_message(
+        &mut ctx.accounts.journal_entry,
+        ctx.accounts.journal_revision.as_deref_mut(),
+        ctx.bumps.journal_revision,
+        now,
+    )?;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.set_message(message, now);
+
+    emit!(RevisionRestored {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        restored_revision: ctx.accounts.restored_revision.revision,
+        revision: journal_entry.revision,
+        content_len: journal_entry.content_len(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Makes an earlier message current again. This is recorded as a new
+/// revision, so in history mode the message it replaces is kept as well.
+#[derive(Accounts)]
+#[instruction(title: String, revision: u32)]
+pub struct RestoreRevision<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        realloc = journal_entry.space_with_message(restored_revision.message.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// The revision being restored.
+    #[account(
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            revision.to_le_bytes().as_ref()
+        ],
+        bump = restored_revision.bump,
+    )]
+    pub restored_revision: Account<'info, JournalRevision>,
+
+    /// Receives the replaced message; required in history mode only.
+    #[account(
+        init,
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            journal_entry.revision.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = JournalRevision::space(journal_entry.message.len())
+    )]
+    pub journal_revision: Option<Account<'info, JournalRevision>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
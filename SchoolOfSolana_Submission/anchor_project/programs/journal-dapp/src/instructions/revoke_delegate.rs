@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+pub fn _revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+    emit!(DelegateRevoked {
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.journal_delegate.delegate,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct RevokeDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [DELEGATE_SEED.as_bytes(), owner.key().as_ref(), delegate.as_ref()],
+        bump = journal_delegate.bump,
+        close = owner,
+    )]
+    pub journal_delegate: Account<'info, JournalDelegate>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::states::*;
+
+pub fn _revoke_reader(ctx: Context<RevokeReader>) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.reader_count -= 1;
+
+    emit!(ReaderRevoked {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        reader: ctx.accounts.entry_reader.reader,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Removes a reader's copy of the content key. Anything the reader already
+/// decrypted stays readable to them; rotate the content key with
+/// `update_encrypted_entry` and call `grant_reader` again for each remaining
+/// reader, which replaces their wrapped key, to cut off future edits as well.
+#[derive(Accounts)]
+#[instruction(title: String, reader: Pubkey)]
+pub struct RevokeReader<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        mut,
+        seeds = [READER_SEED.as_bytes(), journal_entry.key().as_ref(), reader.as_ref()],
+        bump = entry_reader.bump,
+        close = owner,
+    )]
+    pub entry_reader: Account<'info, EntryReader>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
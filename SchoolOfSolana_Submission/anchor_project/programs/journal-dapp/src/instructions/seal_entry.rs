@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _seal_entry<'info>(ctx: Context<'_, '_, 'info, 'info, SealEntry<'info>>) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    if ctx.remaining_accounts.len() != journal_entry.pages.len() {
+        return err!(JournalError::PagesRequired);
+    }
+    let mut pages = Vec::with_capacity(journal_entry.pages.len());
+    for (page_id, info) in journal_entry.pages.iter().zip(ctx.remaining_accounts) {
+        let entry_page = Account::<EntryPage>::try_from(info)?;
+        if entry_page.entry != journal_entry.key() || entry_page.page_id != *page_id {
+            return err!(JournalError::PagesRequired);
+        }
+        pages.push(entry_page.into_inner().content);
+    }
+    let pages: Vec<&str> = pages.iter().map(String::as_str).collect();
+
+    let clock = Clock::get()?;
+    let content_hash = journal_entry.content_hash(&pages);
+    journal_entry.seal = Some(Seal {
+        slot: clock.slot,
+        sealed_at: clock.unix_timestamp,
+        content_hash,
+    });
+
+    emit!(EntrySealed {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        content_hash,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// Freezes an entry for good, recording the slot and a hash of its content.
+/// `remaining_accounts` holds every `EntryPage`, in reading order. Sealed
+/// entries keep accepting reader grants and visibility changes, which don't
+/// touch their content, and `close_all_entries` still closes them.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct SealEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _set_delegate(
+    ctx: Context<SetDelegate>,
+    delegate: Pubkey,
+    permissions: u8,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    if permissions == 0 || permissions & !DELEGATE_ALL != 0 {
+        return err!(JournalError::InvalidPermissions);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return err!(JournalError::InvalidExpiry);
+    }
+
+    let journal_delegate = &mut ctx.accounts.journal_delegate;
+    journal_delegate.owner = ctx.accounts.owner.key();
+    journal_delegate.delegate = delegate;
+    journal_delegate.permissions = permissions;
+    journal_delegate.expires_at = expires_at;
+    journal_delegate.bump = ctx.bumps.journal_delegate;
+
+    emit!(DelegateSet {
+        owner: journal_delegate.owner,
+        delegate,
+        permissions,
+        expires_at,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Grants `delegate` rights on the owner's journal, replacing any earlier
+/// grant to the same wallet.
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct SetDelegate<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [DELEGATE_SEED.as_bytes(), owner.key().as_ref(), delegate.as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + JournalDelegate::INIT_SPACE
+    )]
+    pub journal_delegate: Account<'info, JournalDelegate>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
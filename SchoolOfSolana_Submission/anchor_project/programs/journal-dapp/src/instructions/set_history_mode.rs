@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _set_history_mode(ctx: Context<SetHistoryMode>, enabled: bool) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.history_enabled = enabled;
+
+    emit!(HistoryModeSet {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        title_hash: journal_entry.title_hash(),
+        enabled,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct SetHistoryMode<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.encrypted.is_none() @ JournalError::EntryEncrypted,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    pub owner: Signer<'info>,
+}
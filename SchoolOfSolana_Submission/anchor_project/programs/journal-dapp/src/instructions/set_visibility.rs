@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _set_visibility(ctx: Context<SetVisibility>, visibility: Visibility) -> Result<()> {
+    let entry = ctx.accounts.journal_entry.key();
+    let public_feed = &mut ctx.accounts.public_feed;
+    let feed_page = &mut ctx.accounts.feed_page;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+
+    match (journal_entry.feed_page, visibility) {
+        (None, Visibility::Public) => {
+            // Both feed accounts may have just been created by `init_if_needed`.
+            public_feed.bump = ctx.bumps.public_feed;
+            feed_page.index = public_feed.current_page();
+            feed_page.bump = ctx.bumps.feed_page;
+            public_feed.push_entry(feed_page, entry);
+            journal_entry.feed_page = Some(feed_page.index);
+        }
+        (Some(_), Visibility::Private | Visibility::Unlisted) => {
+            if !feed_page.remove(&entry) {
+                return err!(JournalError::EntryNotIndexed);
+            }
+            public_feed.entry_count -= 1;
+            journal_entry.feed_page = None;
+        }
+        _ => {}
+    }
+    journal_entry.visibility = visibility;
+
+    emit!(VisibilitySet {
+        owner: journal_entry.owner,
+        entry,
+        title_hash: journal_entry.title_hash(),
+        visibility,
+        feed_page: journal_entry.feed_page,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Changes who an entry is meant for, publishing it to or withdrawing it from
+/// the `PublicFeed`. `feed_page` is the page listing the entry when it is
+/// already public, and the feed's current page otherwise. Feed pages are
+/// shared by everyone, so their rent is not refunded.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct SetVisibility<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(
+        init_if_needed,
+        seeds = [PUBLIC_FEED_SEED.as_bytes()],
+        bump,
+        payer = owner,
+        space = 8 + PublicFeed::INIT_SPACE
+    )]
+    pub public_feed: Account<'info, PublicFeed>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            FEED_PAGE_SEED.as_bytes(),
+            journal_entry
+                .feed_page
+                .unwrap_or_else(|| public_feed.current_page())
+                .to_le_bytes()
+                .as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = 8 + PublicFeedPage::INIT_SPACE
+    )]
+    pub feed_page: Account<'info, PublicFeedPage>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::states::*;
+
+pub fn _update_encrypted_entry(ctx: Context<UpdateEncryptedEntry>, content: EncryptedContent) -> Result<()> {
+    content.validate()?;
+    let clock = Clock::get()?;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.set_encrypted(content, clock.unix_timestamp);
+
+    emit!(EntryUpdated {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        authority: ctx.accounts.owner.key(),
+        title_hash: journal_entry.title_hash(),
+        content_len: journal_entry.content_len(),
+        revision: journal_entry.revision,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, content: EncryptedContent)]
+pub struct UpdateEncryptedEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.encrypted.is_some() @ JournalError::EntryNotEncrypted,
+        realloc = journal_entry.space_with_ciphertext(content.ciphertext.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
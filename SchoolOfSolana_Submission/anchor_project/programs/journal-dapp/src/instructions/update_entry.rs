@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::create_pda;
+use crate::states::*;
+
+pub fn _update_entry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateEntry<'info>>,
+    message: String,
+    tags: Option<Vec<String>>,
+) -> Result<()> {
+    let entry = ctx.accounts.journal_entry.key();
+    let authority = ctx.accounts.authority.to_account_info();
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    if let Some(tags) = tags {
+        validate_tags(&tags)?;
+        let old_tags = &ctx.accounts.journal_entry.tags;
+        let removed: Vec<String> = old_tags.iter().filter(|tag| !tags.contains(tag)).cloned().collect();
+        let added: Vec<String> = tags.iter().filter(|tag| !old_tags.contains(tag)).cloned().collect();
+        if ctx.remaining_accounts.len() != removed.len() + added.len() {
+            return err!(JournalError::MissingTagIndexes);
+        }
+        let (removed_indexes, added_indexes) = ctx.remaining_accounts.split_at(removed.len());
+        unindex_tags(entry, &owner, &removed, removed_indexes, &system_program)?;
+        index_tags(entry, owner.key(), &added, added_indexes, &authority, &owner, &system_program)?;
+        ctx.accounts.journal_entry.tags = tags;
+    }
+    write_message(
+        &mut ctx.accounts.journal_entry,
+        ctx.accounts.journal_revision.as_deref_mut(),
+        ctx.bumps.journal_revision,
+        message,
+        &authority,
+        &owner,
+        &system_program,
+    )
+}
+
+/// Stores `message` as the entry's next revision, snapshotting the old one in
+/// history mode, and resizes the entry to fit. Shared by every instruction
+/// that edits a plaintext message.
+pub(crate) fn write_message<'info>(
+    journal_entry: &mut Account<'info, JournalEntry>,
+    journal_revision: Option<&mut JournalRevision>,
+    revision_bump: u8,
+    message: String,
+    authority: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    validate_message(&message)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    snapshot_message(journal_entry, journal_revision, revision_bump, now)?;
+    journal_entry.set_message(message, now);
+    resize_account(
+        &journal_entry.to_account_info(),
+        journal_entry.current_space(),
+        authority,
+        owner,
+        system_program,
+    )?;
+
+    emit!(EntryUpdated {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        authority: authority.key(),
+        title_hash: journal_entry.title_hash(),
+        content_len: journal_entry.content_len(),
+        revision: journal_entry.revision,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Records the current message as a `JournalRevision` when history mode is on.
+/// The revision account has to be passed exactly when history mode is on.
+pub(crate) fn snapshot_message(
+    journal_entry: &mut Account<JournalEntry>,
+    journal_revision: Option<&mut JournalRevision>,
+    revision_bump: u8,
+    now: i64,
+) -> Result<()> {
+    match (journal_entry.history_enabled, journal_revision) {
+        (true, Some(journal_revision)) => {
+            let entry = journal_entry.key();
+            journal_entry.record_revision(entry, journal_revision, revision_bump, now);
+            Ok(())
+        }
+        (true, None) => err!(JournalError::RevisionAccountRequired),
+        (false, Some(_)) => err!(JournalError::HistoryDisabled),
+        (false, None) => Ok(()),
+    }
+}
+
+/// Lists `entry` on the owner's `TagIndex` for each of `tags`, creating the
+/// indexes that don't exist yet. `tag_indexes` holds one account per tag, in
+/// the same order.
+pub(crate) fn index_tags<'info>(
+    entry: Pubkey,
+    owner: Pubkey,
+    tags: &[String],
+    tag_indexes: &'info [AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    refund: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    for (tag, info) in tags.iter().zip(tag_indexes) {
+        let seeds = [TAG_SEED.as_bytes(), owner.as_ref(), tag.as_bytes()];
+        let (address, bump) = Pubkey::find_program_address(&seeds, &crate::ID);
+        if info.key() != address {
+            return err!(JournalError::TagIndexMismatch);
+        }
+
+        if info.owner == &system_program.key() && info.data_is_empty() {
+            let space = TagIndex::space(tag.len(), 1);
+            create_pda(info, space, &[&seeds[..], &[&[bump]]].concat(), payer, system_program)?;
+            let tag_index = TagIndex {
+                owner,
+                tag: tag.clone(),
+                entries: vec![entry],
+                bump,
+            };
+            tag_index.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        } else {
+            let mut tag_index = Account::<TagIndex>::try_from(info)?;
+            if tag_index.entries.len() >= MAX_TAGGED_ENTRIES {
+                return err!(JournalError::TagIndexFull);
+            }
+            let space = TagIndex::space(tag.len(), tag_index.entries.len() + 1);
+            resize_account(info, space, payer, refund, system_program)?;
+            tag_index.entries.push(entry);
+            tag_index.exit(&crate::ID)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes `entry` from the `TagIndex` of each of `tags`, passed in the same
+/// order, and closes indexes that end up empty. Freed rent goes to `owner`.
+pub(crate) fn unindex_tags<'info>(
+    entry: Pubkey,
+    owner: &AccountInfo<'info>,
+    tags: &[String],
+    tag_indexes: &'info [AccountInfo<'info>],
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    for (tag, info) in tags.iter().zip(tag_indexes) {
+        let mut tag_index = Account::<TagIndex>::try_from(info)?;
+        if tag_index.owner != owner.key() || tag_index.tag != *tag || !tag_index.entries.contains(&entry) {
+            return err!(JournalError::TagIndexMismatch);
+        }
+        tag_index.entries.retain(|tagged| *tagged != entry);
+        if tag_index.entries.is_empty() {
+            tag_index.close(owner.clone())?;
+        } else {
+            let space = TagIndex::space(tag.len(), tag_index.entries.len());
+            resize_account(info, space, owner, owner, system_program)?;
+            tag_index.exit(&crate::ID)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces `old` with `new` on the `TagIndex` of each of `tags`, passed in
+/// the same order, for an entry that moved without changing owner.
+pub(crate) fn retag_entry<'info>(
+    old: Pubkey,
+    new: Pubkey,
+    owner: Pubkey,
+    tags: &[String],
+    tag_indexes: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    if tag_indexes.len() != tags.len() {
+        return err!(JournalError::MissingTagIndexes);
+    }
+    for (tag, info) in tags.iter().zip(tag_indexes) {
+        let mut tag_index = Account::<TagIndex>::try_from(info)?;
+        let position = tag_index.entries.iter().position(|tagged| *tagged == old);
+        match position {
+            Some(position) if tag_index.owner == owner && tag_index.tag == *tag => {
+                tag_index.entries[position] = new;
+                tag_index.exit(&crate::ID)?;
+            }
+            _ => return err!(JournalError::TagIndexMismatch),
+        }
+    }
+    Ok(())
+}
+
+/// Resizes `account` to `space` bytes. Missing rent is paid by `payer` and
+/// excess rent goes to `refund`, so a delegate shrinking an entry cannot
+/// collect the owner's deposit.
+pub(crate) fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    refund: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let balance = account.lamports();
+    if rent > balance {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_context = CpiContext::new(system_program.clone(), cpi_accounts);
+        system_program::transfer(cpi_context, rent - balance)?;
+    } else {
+        **account.try_borrow_mut_lamports()? -= balance - rent;
+        **refund.try_borrow_mut_lamports()? += balance - rent;
+    }
+    account.realloc(space, false)?;
+    Ok(())
+}
+
+/// Replaces an entry's message and, when `tags` is given, its tags. The owner
+/// may always do so; a delegate needs `DELEGATE_EDIT`, or `DELEGATE_APPEND`
+/// when the new message only extends the current one and the tags stay.
+/// `remaining_accounts` holds the `TagIndex` of every removed tag, in the
+/// entry's current order, followed by that of every added tag, in the new
+/// order.
+#[derive(Accounts)]
+#[instruction(title: String, message: String, tags: Option<Vec<String>>)]
+pub struct UpdateEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), entry_seed(&title, journal_entry.notebook).as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = journal_entry.version == ENTRY_VERSION @ JournalError::OutdatedEntry,
+        constraint = journal_entry.archived_at.is_none() @ JournalError::EntryArchived,
+        constraint = journal_entry.seal.is_none() @ JournalError::EntrySealed,
+        constraint = journal_entry.encrypted.is_none() @ JournalError::EntryEncrypted,
+    )]
+    pub journal_entry: Account<'info, JournalEntry>,
+
+    /// Receives the replaced message; required in history mode only.
+    #[account(
+        init,
+        seeds = [
+            REVISION_SEED.as_bytes(),
+            journal_entry.key().as_ref(),
+            journal_entry.revision.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = JournalRevision::space(journal_entry.message.len())
+    )]
+    pub journal_revision: Option<Account<'info, JournalRevision>>,
+
+    /// Grant held by `authority`; omitted when the owner signs.
+    #[account(
+        seeds = [DELEGATE_SEED.as_bytes(), owner.key().as_ref(), authority.key().as_ref()],
+        bump = journal_delegate.bump,
+    )]
+    pub journal_delegate: Option<Account<'info, JournalDelegate>>,
+
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(
+        mut,
+        constraint = JournalDelegate::authorizes(
+            journal_delegate.as_deref(),
+            &owner.key(),
+            &authority.key(),
+            journal_entry.update_permissions(&message, tags.as_deref()),
+        )? @ JournalError::NotAuthorized,
+    )]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
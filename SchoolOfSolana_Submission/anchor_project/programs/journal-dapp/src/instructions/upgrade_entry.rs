@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::JournalError;
+use crate::events::*;
+use crate::instructions::resize_account;
+use crate::states::*;
+
+pub fn _upgrade_entry(ctx: Context<UpgradeEntry>) -> Result<()> {
+    let entry_info = ctx.accounts.journal_entry.to_account_info();
+    if JournalEntry::is_current(&entry_info.try_borrow_data()?) {
+        return err!(JournalError::EntryUpToDate);
+    }
+
+    // 1. Resize the account to fit its content, settling rent with the owner
+    let legacy_entry = &ctx.accounts.journal_entry;
+    let owner = ctx.accounts.owner.to_account_info();
+    resize_account(
+        &entry_info,
+        JournalEntry::space(legacy_entry.title.len(), legacy_entry.message.len()),
+        &owner,
+        &owner,
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // 2. Rewrite the entry; the original creation time was never recorded
+    let journal_entry = JournalEntry::new(
+        legacy_entry.owner,
+        legacy_entry.title.clone(),
+        legacy_entry.message.clone(),
+        Clock::get()?.unix_timestamp,
+    );
+    journal_entry.try_serialize(&mut &mut entry_info.try_borrow_mut_data()?[..])?;
+
+    emit!(EntryUpgraded {
+        owner: journal_entry.owner,
+        entry: entry_info.key(),
+        title_hash: journal_entry.title_hash(),
+        content_len: journal_entry.content_len(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Rewrites an entry stored at its hash-based address in the pre-versioning
+/// layout. Entries still at their original `[title, owner]` address go
+/// through `migrate_entry` instead.
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct UpgradeEntry<'info> {
+    #[account(
+        mut,
+        seeds = [ENTRY_SEED.as_bytes(), title_seed(&title).as_ref(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, LegacyJournalEntry>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
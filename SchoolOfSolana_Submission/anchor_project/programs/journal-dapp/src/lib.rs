@@ -1,5 +1,15 @@
+#![allow(unexpected_cfgs)]
+
 use anchor_lang::prelude::*;
 
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod states;
+
+use instructions::*;
+use states::{EncryptedContent, NewEntry, Visibility, WRAPPED_KEY_LENGTH};
+
 // ⚠️ STEP 1: DEPLOY ONCE, THEN REPLACE THIS ID WITH YOUR OWN FROM THE TERMINAL
 declare_id!("EJTGjYQmVnedbzSTHGoqx67n5Pe4w9hnYa72C8DkBx3t");
 
@@ -7,81 +17,225 @@ declare_id!("EJTGjYQmVnedbzSTHGoqx67n5Pe4w9hnYa72C8DkBx3t");
 pub mod journal_dapp {
     use super::*;
 
-    pub fn create_entry(ctx: Context<CreateEntry>, title: String, message: String) -> Result<()> {
-        let journal_entry = &mut ctx.accounts.journal_entry;
-        journal_entry.owner = *ctx.accounts.owner.key;
-        journal_entry.title = title;
-        journal_entry.message = message;
-        Ok(())
+    pub fn create_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateEntry<'info>>,
+        title: String,
+        message: String,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        _create_entry(ctx, title, message, tags)
     }
 
-    pub fn update_entry(ctx: Context<UpdateEntry>, _title: String, message: String) -> Result<()> {
-        let journal_entry = &mut ctx.accounts.journal_entry;
-        journal_entry.message = message;
-        Ok(())
+    pub fn update_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateEntry<'info>>,
+        _title: String,
+        message: String,
+        tags: Option<Vec<String>>,
+    ) -> Result<()> {
+        _update_entry(ctx, message, tags)
     }
 
-    pub fn delete_entry(_ctx: Context<DeleteEntry>, _title: String) -> Result<()> {
-        Ok(())
+    pub fn seal_entry<'info>(ctx: Context<'_, '_, 'info, 'info, SealEntry<'info>>, _title: String) -> Result<()> {
+        _seal_entry(ctx)
     }
-}
 
-// --- STATE ---
-#[account]
-#[derive(InitSpace)]
-pub struct JournalEntry {
-    pub owner: Pubkey,
-    #[max_len(50)]
-    pub title: String,
-    #[max_len(1000)]
-    pub message: String,
-}
+    pub fn notarize(
+        ctx: Context<Notarize>,
+        title: String,
+        sha256: [u8; 32],
+        uri: Option<String>,
+        mime: Option<String>,
+    ) -> Result<()> {
+        _notarize(ctx, title, sha256, uri, mime)
+    }
 
-// --- ACCOUNTS ---
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct CreateEntry<'info> {
-    #[account(
-        init,
-        seeds = [title.as_bytes(), owner.key().as_ref()], 
-        bump, 
-        payer = owner, 
-        space = 8 + JournalEntry::INIT_SPACE
-    )]
-    pub journal_entry: Account<'info, JournalEntry>,
-
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn append_to_entry(ctx: Context<AppendToEntry>, _title: String, text: String) -> Result<()> {
+        _append_to_entry(ctx, text)
+    }
 
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct UpdateEntry<'info> {
-    #[account(
-        mut,
-        seeds = [title.as_bytes(), owner.key().as_ref()], 
-        bump, 
-    )]
-    pub journal_entry: Account<'info, JournalEntry>,
-
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn patch_entry(
+        ctx: Context<PatchEntry>,
+        _title: String,
+        offset: u32,
+        len: u32,
+        replacement: String,
+    ) -> Result<()> {
+        _patch_entry(ctx, offset, len, replacement)
+    }
 
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct DeleteEntry<'info> {
-    #[account(
-        mut,
-        seeds = [title.as_bytes(), owner.key().as_ref()], 
-        bump, 
-        close = owner,
-    )]
-    pub journal_entry: Account<'info, JournalEntry>,
-
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub fn create_entries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateEntries<'info>>,
+        entries: Vec<NewEntry>,
+    ) -> Result<()> {
+        _create_entries(ctx, entries)
+    }
+
+    pub fn delete_entries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeleteEntries<'info>>,
+        titles: Vec<String>,
+    ) -> Result<()> {
+        _delete_entries(ctx, titles)
+    }
+
+    pub fn delete_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeleteEntry<'info>>,
+        _title: String,
+    ) -> Result<()> {
+        _delete_entry(ctx)
+    }
+
+    pub fn archive_entry(ctx: Context<ArchiveEntry>, _title: String, grace_period: Option<u32>) -> Result<()> {
+        _archive_entry(ctx, grace_period)
+    }
+
+    pub fn restore_entry(ctx: Context<RestoreEntry>, _title: String) -> Result<()> {
+        _restore_entry(ctx)
+    }
+
+    pub fn purge_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurgeEntry<'info>>,
+        _title: String,
+    ) -> Result<()> {
+        _purge_entry(ctx)
+    }
+
+    pub fn set_visibility(ctx: Context<SetVisibility>, _title: String, visibility: Visibility) -> Result<()> {
+        _set_visibility(ctx, visibility)
+    }
+
+    pub fn create_notebook(ctx: Context<CreateNotebook>, name: String, description: String) -> Result<()> {
+        _create_notebook(ctx, name, description)
+    }
+
+    pub fn rename_notebook(ctx: Context<RenameNotebook>, _notebook_id: u32, name: String) -> Result<()> {
+        _rename_notebook(ctx, name)
+    }
+
+    pub fn close_notebook(ctx: Context<CloseNotebook>, _notebook_id: u32) -> Result<()> {
+        _close_notebook(ctx)
+    }
+
+    pub fn move_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MoveEntry<'info>>,
+        title: String,
+        notebook_id: u32,
+    ) -> Result<()> {
+        _move_entry(ctx, title, notebook_id)
+    }
+
+    pub fn rename_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RenameEntry<'info>>,
+        _old_title: String,
+        new_title: String,
+    ) -> Result<()> {
+        _rename_entry(ctx, new_title)
+    }
+
+    pub fn migrate_entry(ctx: Context<MigrateEntry>, title: String) -> Result<()> {
+        _migrate_entry(ctx, title)
+    }
+
+    pub fn upgrade_entry(ctx: Context<UpgradeEntry>, _title: String) -> Result<()> {
+        _upgrade_entry(ctx)
+    }
+
+    pub fn set_history_mode(ctx: Context<SetHistoryMode>, _title: String, enabled: bool) -> Result<()> {
+        _set_history_mode(ctx, enabled)
+    }
+
+    pub fn read_revision(ctx: Context<ReadRevision>, _title: String, _revision: u32) -> Result<String> {
+        _read_revision(ctx)
+    }
+
+    pub fn restore_revision(ctx: Context<RestoreRevision>, _title: String, _revision: u32) -> Result<()> {
+        _restore_revision(ctx)
+    }
+
+    pub fn prune_revision(ctx: Context<PruneRevision>, _title: String, _revision: u32) -> Result<()> {
+        _prune_revision(ctx)
+    }
+
+    pub fn append_page(ctx: Context<AppendPage>, _title: String, content: String) -> Result<()> {
+        _append_page(ctx, content)
+    }
+
+    pub fn replace_page(ctx: Context<ReplacePage>, _title: String, _page_id: u16, content: String) -> Result<()> {
+        _replace_page(ctx, content)
+    }
+
+    pub fn remove_page(ctx: Context<RemovePage>, _title: String, page_id: u16) -> Result<()> {
+        _remove_page(ctx, page_id)
+    }
+
+    pub fn create_encrypted_entry(
+        ctx: Context<CreateEncryptedEntry>,
+        title: String,
+        content: EncryptedContent,
+    ) -> Result<()> {
+        _create_encrypted_entry(ctx, title, content)
+    }
+
+    pub fn update_encrypted_entry(
+        ctx: Context<UpdateEncryptedEntry>,
+        _title: String,
+        content: EncryptedContent,
+    ) -> Result<()> {
+        _update_encrypted_entry(ctx, content)
+    }
+
+    pub fn grant_reader(
+        ctx: Context<GrantReader>,
+        _title: String,
+        reader: Pubkey,
+        wrapped_key: [u8; WRAPPED_KEY_LENGTH],
+    ) -> Result<()> {
+        _grant_reader(ctx, reader, wrapped_key)
+    }
+
+    pub fn revoke_reader(ctx: Context<RevokeReader>, _title: String, _reader: Pubkey) -> Result<()> {
+        _revoke_reader(ctx)
+    }
+
+    pub fn set_delegate(
+        ctx: Context<SetDelegate>,
+        delegate: Pubkey,
+        permissions: u8,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        _set_delegate(ctx, delegate, permissions, expires_at)
+    }
+
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>, _delegate: Pubkey) -> Result<()> {
+        _revoke_delegate(ctx)
+    }
+
+    pub fn close_all_entries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseAllEntries<'info>>,
+        _page_index: u64,
+        entry_count: u8,
+    ) -> Result<()> {
+        _close_all_entries(ctx, entry_count)
+    }
+
+    pub fn close_journal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseJournal<'info>>,
+        page_count: u8,
+    ) -> Result<()> {
+        _close_journal(ctx, page_count)
+    }
+
+    pub fn offer_transfer(ctx: Context<OfferTransfer>, _title: String, recipient: Pubkey) -> Result<()> {
+        _offer_transfer(ctx, recipient)
+    }
+
+    pub fn accept_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptTransfer<'info>>,
+        title: String,
+    ) -> Result<()> {
+        _accept_transfer(ctx, title)
+    }
+
+    pub fn cancel_transfer(ctx: Context<CancelTransfer>, _entry: Pubkey) -> Result<()> {
+        _cancel_transfer(ctx)
+    }
 }
@@ -0,0 +1,831 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::Discriminator;
+
+use crate::errors::JournalError;
+
+pub const TITLE_LENGTH: usize = 50;
+pub const MESSAGE_LENGTH: usize = 1000;
+pub const PAGE_LENGTH: usize = 1000;
+pub const MAX_PAGES: usize = 64;
+
+pub const NONCE_LENGTH: usize = 24;
+pub const AUTH_TAG_LENGTH: usize = 16;
+/// Ciphertext of a full-length message plus its authentication tag.
+pub const MAX_CIPHERTEXT_LENGTH: usize = MESSAGE_LENGTH + AUTH_TAG_LENGTH;
+/// Ephemeral X25519 public key, nonce and the sealed 32-byte content key.
+pub const WRAPPED_KEY_LENGTH: usize = 32 + NONCE_LENGTH + 32 + AUTH_TAG_LENGTH;
+
+pub const ENTRIES_PER_PAGE: usize = 32;
+
+pub const MAX_TAGS: usize = 5;
+/// Tags double as seeds, so they must fit the 32-byte seed limit.
+pub const TAG_LENGTH: usize = 32;
+pub const MAX_TAGGED_ENTRIES: usize = 256;
+
+/// Layout version written by this program; see `LegacyJournalEntry`.
+pub const ENTRY_VERSION: u8 = 1;
+
+pub const ENTRY_SEED: &str = "JOURNAL_ENTRY";
+pub const USER_JOURNAL_SEED: &str = "USER_JOURNAL";
+pub const JOURNAL_PAGE_SEED: &str = "JOURNAL_PAGE";
+pub const REVISION_SEED: &str = "JOURNAL_REVISION";
+pub const ENTRY_PAGE_SEED: &str = "ENTRY_PAGE";
+pub const READER_SEED: &str = "ENTRY_READER";
+pub const DELEGATE_SEED: &str = "JOURNAL_DELEGATE";
+pub const PUBLIC_FEED_SEED: &str = "PUBLIC_FEED";
+pub const FEED_PAGE_SEED: &str = "PUBLIC_FEED_PAGE";
+pub const NOTEBOOK_SEED: &str = "NOTEBOOK";
+pub const TAG_SEED: &str = "TAG_INDEX";
+pub const TRANSFER_SEED: &str = "TRANSFER_OFFER";
+
+pub const NOTEBOOK_NAME_LENGTH: usize = 50;
+pub const DESCRIPTION_LENGTH: usize = 280;
+
+pub const URI_LENGTH: usize = 200;
+pub const MIME_LENGTH: usize = 100;
+
+/// Lets a delegate extend a message without changing what is already there.
+pub const DELEGATE_APPEND: u8 = 1 << 0;
+/// Lets a delegate replace a message; implies `DELEGATE_APPEND`.
+pub const DELEGATE_EDIT: u8 = 1 << 1;
+pub const DELEGATE_DELETE: u8 = 1 << 2;
+pub const DELEGATE_ALL: u8 = DELEGATE_APPEND | DELEGATE_EDIT | DELEGATE_DELETE;
+
+/// Canonical form of a title used for PDA derivation: surrounding whitespace
+/// is ignored and ASCII letters are compared case-insensitively.
+pub fn normalize_title(title: &str) -> String {
+    title.trim().to_ascii_lowercase()
+}
+
+/// Fixed-size seed for a title, so any title up to `TITLE_LENGTH` bytes fits
+/// within the 32-byte seed limit.
+pub fn title_seed(title: &str) -> [u8; 32] {
+    hash(normalize_title(title).as_bytes()).to_bytes()
+}
+
+/// Seed locating an entry within its notebook, so every notebook has its own
+/// title namespace. Entries outside any notebook (notebook 0) are seeded by
+/// the plain `title_seed`.
+pub fn entry_seed(title: &str, notebook: u32) -> [u8; 32] {
+    if notebook == 0 {
+        return title_seed(title);
+    }
+    hashv(&[&notebook.to_le_bytes(), normalize_title(title).as_bytes()]).to_bytes()
+}
+
+/// Rejects titles that are blank, contain control characters or do not fit
+/// in `TITLE_LENGTH` bytes.
+pub fn validate_title(title: &str) -> Result<()> {
+    if title.trim().is_empty() {
+        return err!(JournalError::TitleEmpty);
+    }
+    if title.chars().any(char::is_control) {
+        return err!(JournalError::TitleControlCharacter);
+    }
+    check_length(
+        title,
+        TITLE_LENGTH,
+        JournalError::TitleTooLong,
+        JournalError::TitleTooLongUtf8,
+    )
+}
+
+pub fn validate_message(message: &str) -> Result<()> {
+    check_length(
+        message,
+        MESSAGE_LENGTH,
+        JournalError::MessageTooLong,
+        JournalError::MessageTooLongUtf8,
+    )
+}
+
+/// Tags are 1 to `TAG_LENGTH` bytes of lowercase ASCII letters, digits, `-`
+/// and `_`, so equal tags always have equal bytes. An entry has at most
+/// `MAX_TAGS` of them, without duplicates.
+pub fn validate_tags(tags: &[String]) -> Result<()> {
+    if tags.len() > MAX_TAGS {
+        return err!(JournalError::TooManyTags);
+    }
+    for (i, tag) in tags.iter().enumerate() {
+        let valid_char = |c: u8| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-' || c == b'_';
+        if tag.is_empty() || tag.len() > TAG_LENGTH || !tag.bytes().all(valid_char) {
+            return err!(JournalError::InvalidTag);
+        }
+        if tags[..i].contains(tag) {
+            return err!(JournalError::DuplicateTag);
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_notebook_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return err!(JournalError::NotebookNameEmpty);
+    }
+    check_length(
+        name,
+        NOTEBOOK_NAME_LENGTH,
+        JournalError::NotebookNameTooLong,
+        JournalError::NotebookNameTooLong,
+    )
+}
+
+pub fn validate_description(description: &str) -> Result<()> {
+    check_length(
+        description,
+        DESCRIPTION_LENGTH,
+        JournalError::DescriptionTooLong,
+        JournalError::DescriptionTooLong,
+    )
+}
+
+pub fn validate_notarization(uri: Option<&str>, mime: Option<&str>) -> Result<()> {
+    if let Some(uri) = uri {
+        check_length(uri, URI_LENGTH, JournalError::UriTooLong, JournalError::UriTooLong)?;
+    }
+    if let Some(mime) = mime {
+        check_length(mime, MIME_LENGTH, JournalError::MimeTooLong, JournalError::MimeTooLong)?;
+    }
+    Ok(())
+}
+
+pub fn validate_page(content: &str) -> Result<()> {
+    check_length(
+        content,
+        PAGE_LENGTH,
+        JournalError::PageTooLong,
+        JournalError::PageTooLongUtf8,
+    )
+}
+
+/// Limits are in bytes. Text that would fit if counted in characters gets
+/// its own error, as clients usually count characters.
+fn check_length(text: &str, max_len: usize, too_long: JournalError, too_long_utf8: JournalError) -> Result<()> {
+    if text.len() <= max_len {
+        Ok(())
+    } else if text.chars().count() <= max_len {
+        Err(too_long_utf8.into())
+    } else {
+        Err(too_long.into())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct JournalEntry {
+    pub owner: Pubkey,
+    #[max_len(TITLE_LENGTH)]
+    pub title: String,
+    #[max_len(MESSAGE_LENGTH)]
+    pub message: String,
+    pub version: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub revision: u32,
+    pub history_enabled: bool,
+    /// Head of the hash chain over every revision recorded in history mode.
+    pub history_hash: [u8; 32],
+    /// Number of `JournalRevision` accounts that have not been pruned.
+    pub history_len: u32,
+    /// Ids of the entry's `EntryPage`s in reading order; empty unless the
+    /// entry is long-form.
+    #[max_len(MAX_PAGES)]
+    pub pages: Vec<u16>,
+    pub next_page_id: u16,
+    /// Set for encrypted entries, whose `message` stays empty.
+    pub encrypted: Option<EncryptedContent>,
+    /// Number of `EntryReader` grants on the entry.
+    pub reader_count: u16,
+    /// Set while the entry is archived. Archived entries keep their index
+    /// slot, but listings skip them and they can only be restored or purged.
+    pub archived_at: Option<i64>,
+    /// Earliest time `purge_entry` may close an archived entry.
+    pub purge_after: Option<i64>,
+    pub visibility: Visibility,
+    /// Index of the `PublicFeedPage` listing the entry while it is public.
+    pub feed_page: Option<u64>,
+    /// Id of the `Notebook` holding the entry; 0 when it is in none.
+    pub notebook: u32,
+    /// Each tag lists the entry on the owner's `TagIndex` for it.
+    #[max_len(MAX_TAGS, TAG_LENGTH)]
+    pub tags: Vec<String>,
+    /// Set once the entry is sealed, after which it can never change again.
+    pub seal: Option<Seal>,
+    /// Set for notarized entries, whose `message` stays empty.
+    pub notarization: Option<Notarization>,
+}
+
+impl JournalEntry {
+    /// Account size with every variable-length field empty.
+    const BASE_SPACE: usize = 8 + JournalEntry::INIT_SPACE
+        - TITLE_LENGTH
+        - MESSAGE_LENGTH
+        - 2 * MAX_PAGES
+        - EncryptedContent::INIT_SPACE
+        - MAX_TAGS * (4 + TAG_LENGTH)
+        - Notarization::INIT_SPACE;
+
+    /// Bytes taken by `tags` beyond the empty list.
+    pub fn tags_space(tags: &[String]) -> usize {
+        tags.iter().map(|tag| 4 + tag.len()).sum()
+    }
+
+    /// Account size for a new entry holding exactly this title and message.
+    pub fn space(title_len: usize, message_len: usize) -> usize {
+        Self::BASE_SPACE + title_len + message_len
+    }
+
+    /// Account size for a new encrypted entry.
+    pub fn encrypted_space(title_len: usize, ciphertext_len: usize) -> usize {
+        Self::BASE_SPACE + title_len + EncryptedContent::space(ciphertext_len)
+    }
+
+    /// Account size for a new notarized entry.
+    pub fn notarized_space(title_len: usize, uri: Option<&str>, mime: Option<&str>) -> usize {
+        Self::BASE_SPACE + title_len + Notarization::space(uri, mime)
+    }
+
+    /// Account size for this entry as it currently is.
+    pub fn current_space(&self) -> usize {
+        Self::BASE_SPACE
+            + self.title.len()
+            + self.message.len()
+            + 2 * self.pages.len()
+            + Self::tags_space(&self.tags)
+            + self
+                .encrypted
+                .as_ref()
+                .map_or(0, |content| EncryptedContent::space(content.ciphertext.len()))
+            + self
+                .notarization
+                .as_ref()
+                .map_or(0, |notarization| {
+                    Notarization::space(notarization.uri.as_deref(), notarization.mime.as_deref())
+                })
+    }
+
+    /// Account size for this entry once its ciphertext is replaced.
+    pub fn space_with_ciphertext(&self, ciphertext_len: usize) -> usize {
+        self.current_space()
+            - self
+                .encrypted
+                .as_ref()
+                .map_or(0, |content| content.ciphertext.len())
+            + ciphertext_len
+    }
+
+    /// Account size for this entry once its title is replaced.
+    pub fn space_with_title(&self, title_len: usize) -> usize {
+        self.current_space() - self.title.len() + title_len
+    }
+
+    /// Number of accounts `close_children` closes along with the entry.
+    pub fn child_count(&self) -> usize {
+        self.pages.len() + self.reader_count as usize + self.tags.len() + self.history_len as usize
+    }
+
+    /// Whether any account is seeded by this entry's address: pages, reader
+    /// grants or stored revisions. Tag indexes only list the address, so
+    /// they are updated rather than counted here.
+    pub fn has_children(&self) -> bool {
+        !self.pages.is_empty() || self.reader_count > 0 || self.history_len > 0
+    }
+
+    /// Account size for this entry once its message is replaced.
+    pub fn space_with_message(&self, message_len: usize) -> usize {
+        self.current_space() - self.message.len() + message_len
+    }
+
+    pub fn new(owner: Pubkey, title: String, message: String, now: i64) -> Self {
+        Self {
+            owner,
+            title,
+            message,
+            version: ENTRY_VERSION,
+            created_at: now,
+            updated_at: now,
+            revision: 0,
+            history_enabled: false,
+            history_hash: [0; 32],
+            history_len: 0,
+            pages: Vec::new(),
+            next_page_id: 0,
+            encrypted: None,
+            reader_count: 0,
+            archived_at: None,
+            purge_after: None,
+            visibility: Visibility::Private,
+            feed_page: None,
+            notebook: 0,
+            tags: Vec::new(),
+            seal: None,
+            notarization: None,
+        }
+    }
+
+    /// Hash identifying the entry's title in events; its `title_seed`.
+    pub fn title_hash(&self) -> [u8; 32] {
+        title_seed(&self.title)
+    }
+
+    /// Bytes of content: the message, or the ciphertext of an encrypted entry.
+    pub fn content_len(&self) -> u32 {
+        self.encrypted
+            .as_ref()
+            .map_or(self.message.len(), |content| content.ciphertext.len()) as u32
+    }
+
+    /// Delegate permissions that allow replacing the message with `message`
+    /// and, if given, the tags with `tags`.
+    pub fn update_permissions(&self, message: &str, tags: Option<&[String]>) -> u8 {
+        let retagged = tags.is_some_and(|tags| tags != self.tags.as_slice());
+        if message.starts_with(self.message.as_str()) && !retagged {
+            DELEGATE_APPEND | DELEGATE_EDIT
+        } else {
+            DELEGATE_EDIT
+        }
+    }
+
+    /// Delegate permissions that allow replacing `len` bytes of the message
+    /// at `offset`. Inserting at the very end is an append.
+    pub fn patch_permissions(&self, offset: u32, len: u32) -> u8 {
+        if offset as usize == self.message.len() && len == 0 {
+            DELEGATE_APPEND | DELEGATE_EDIT
+        } else {
+            DELEGATE_EDIT
+        }
+    }
+
+    /// The message with the `len` bytes at `offset` replaced by
+    /// `replacement`. The range must lie within the message and must not
+    /// split a character.
+    pub fn patched_message(&self, offset: u32, len: u32, replacement: &str) -> Result<String> {
+        let start = offset as usize;
+        let end = start.checked_add(len as usize).ok_or(JournalError::PatchOutOfBounds)?;
+        if end > self.message.len() {
+            return err!(JournalError::PatchOutOfBounds);
+        }
+        if !self.message.is_char_boundary(start) || !self.message.is_char_boundary(end) {
+            return err!(JournalError::PatchNotCharBoundary);
+        }
+        let mut message = String::with_capacity(self.message.len() - (end - start) + replacement.len());
+        message.push_str(&self.message[..start]);
+        message.push_str(replacement);
+        message.push_str(&self.message[end..]);
+        Ok(message)
+    }
+
+    /// SHA-256 of the entry's full content: the message, or the ciphertext of
+    /// an encrypted entry, followed by `pages` in reading order. For a
+    /// plaintext entry this is the hash of its text as read, and for a
+    /// notarized entry the hash of its document.
+    pub fn content_hash(&self, pages: &[&str]) -> [u8; 32] {
+        if let Some(notarization) = &self.notarization {
+            return notarization.sha256;
+        }
+        let content = self
+            .encrypted
+            .as_ref()
+            .map_or(self.message.as_bytes(), |content| content.ciphertext.as_slice());
+        let mut parts = vec![content];
+        parts.extend(pages.iter().map(|page| page.as_bytes()));
+        hashv(&parts).to_bytes()
+    }
+
+    /// Replaces the message as the next revision.
+    pub fn set_message(&mut self, message: String, now: i64) {
+        self.message = message;
+        self.updated_at = now;
+        self.revision += 1;
+    }
+
+    /// Replaces the ciphertext of an encrypted entry as the next revision.
+    pub fn set_encrypted(&mut self, content: EncryptedContent, now: i64) {
+        self.encrypted = Some(content);
+        self.updated_at = now;
+        self.revision += 1;
+    }
+
+    /// Copies the current message into `journal_revision` and extends the
+    /// history chain with it. Must run before `set_message`.
+    pub fn record_revision(
+        &mut self,
+        entry: Pubkey,
+        journal_revision: &mut JournalRevision,
+        bump: u8,
+        now: i64,
+    ) {
+        journal_revision.entry = entry;
+        journal_revision.revision = self.revision;
+        journal_revision.message = self.message.clone();
+        journal_revision.recorded_at = now;
+        journal_revision.prev_hash = self.history_hash;
+        journal_revision.bump = bump;
+        self.history_hash = journal_revision.chain_hash();
+        self.history_len += 1;
+    }
+
+    /// Whether `data` holds an entry already written with the current layout.
+    pub fn is_current(data: &[u8]) -> bool {
+        JournalEntry::try_deserialize(&mut &data[..])
+            .map(|entry| entry.version == ENTRY_VERSION)
+            .unwrap_or(false)
+    }
+}
+
+/// Who an entry is meant for. Account data is readable by anyone on-chain,
+/// so this steers listings and frontends; encrypt entries to keep them secret.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum Visibility {
+    /// Shown to the owner only.
+    Private,
+    /// Shown to anyone who has the entry's address, but not in the feed.
+    Unlisted,
+    /// Listed in the `PublicFeed`.
+    Public,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum EncryptionScheme {
+    /// XChaCha20-Poly1305 under a key derived from the owner's keypair.
+    XChaCha20Poly1305,
+    /// XChaCha20-Poly1305 under a random per-entry content key, which is
+    /// handed to each reader, the owner included, through an `EntryReader`.
+    XChaCha20Poly1305Envelope,
+}
+
+/// Title and message of one entry in a `create_entries` batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct NewEntry {
+    pub title: String,
+    pub message: String,
+}
+
+/// Proof of what a sealed entry held. Anyone can recompute `content_hash`
+/// from the entry and its pages and compare.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct Seal {
+    pub slot: u64,
+    pub sealed_at: i64,
+    pub content_hash: [u8; 32],
+}
+
+/// Off-chain document attested by a notarized entry. Only its hash is
+/// stored; `uri` and `mime` are optional hints for finding and opening it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct Notarization {
+    pub sha256: [u8; 32],
+    #[max_len(URI_LENGTH)]
+    pub uri: Option<String>,
+    #[max_len(MIME_LENGTH)]
+    pub mime: Option<String>,
+}
+
+impl Notarization {
+    /// Serialized size with this metadata, without the `Option` tag.
+    pub fn space(uri: Option<&str>, mime: Option<&str>) -> usize {
+        Notarization::INIT_SPACE - URI_LENGTH - MIME_LENGTH - 8
+            + uri.map_or(0, |uri| 4 + uri.len())
+            + mime.map_or(0, |mime| 4 + mime.len())
+    }
+}
+
+/// Message encrypted client-side; the program only checks its size.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct EncryptedContent {
+    pub scheme: EncryptionScheme,
+    pub nonce: [u8; NONCE_LENGTH],
+    #[max_len(MAX_CIPHERTEXT_LENGTH)]
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedContent {
+    /// Serialized size for this much ciphertext, without the `Option` tag.
+    pub fn space(ciphertext_len: usize) -> usize {
+        EncryptedContent::INIT_SPACE - MAX_CIPHERTEXT_LENGTH + ciphertext_len
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.ciphertext.len() < AUTH_TAG_LENGTH {
+            return err!(JournalError::CiphertextTooShort);
+        }
+        if self.ciphertext.len() > MAX_CIPHERTEXT_LENGTH {
+            return err!(JournalError::CiphertextTooLong);
+        }
+        Ok(())
+    }
+}
+
+/// Grants `reader` access to an envelope-encrypted entry by storing the
+/// entry's content key sealed to the reader's public key.
+#[account]
+#[derive(InitSpace)]
+pub struct EntryReader {
+    pub entry: Pubkey,
+    pub reader: Pubkey,
+    pub wrapped_key: [u8; WRAPPED_KEY_LENGTH],
+    pub granted_at: i64,
+    pub bump: u8,
+}
+
+/// Layout of entries written before `JournalEntry` was versioned. It shares
+/// the `JournalEntry` discriminator and field prefix, so it is decoded by hand;
+/// `migrate_entry` and `upgrade_entry` rewrite it in the current layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyJournalEntry {
+    pub owner: Pubkey,
+    pub title: String,
+    pub message: String,
+}
+
+impl AccountDeserialize for LegacyJournalEntry {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < 8 || buf[..8] != JournalEntry::DISCRIMINATOR {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = &buf[8..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+    }
+}
+
+// Legacy accounts are only ever read and then closed or rewritten in place.
+impl AccountSerialize for LegacyJournalEntry {}
+
+impl Owner for LegacyJournalEntry {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+/// Per-owner index of journal entries. Entry ids are handed out in order and
+/// never reused, so entry `id` always lives in page `id / ENTRIES_PER_PAGE`.
+#[account]
+#[derive(InitSpace)]
+pub struct UserJournal {
+    pub owner: Pubkey,
+    pub entry_count: u64,
+    pub next_entry_id: u64,
+    pub bump: u8,
+    /// Id of the most recently created notebook. Ids start at 1 and are
+    /// never reused.
+    pub last_notebook_id: u32,
+    /// Index pages below this index were closed by `close_journal`.
+    pub closed_pages: u64,
+    /// Notebooks up to this id were closed by `close_journal`.
+    pub closed_notebooks: u32,
+}
+
+impl UserJournal {
+    /// Page that receives the next created entry.
+    pub fn current_page(&self) -> u64 {
+        self.next_entry_id / ENTRIES_PER_PAGE as u64
+    }
+
+    /// Number of pages that have been allocated so far.
+    pub fn page_count(&self) -> u64 {
+        self.next_entry_id.div_ceil(ENTRIES_PER_PAGE as u64)
+    }
+
+    /// Assigns the next entry id to `entry` and lists it on `page`, which must
+    /// be the page returned by `current_page`.
+    pub fn push_entry(&mut self, page: &mut JournalPage, entry: Pubkey) {
+        page.entries.push(entry);
+        self.next_entry_id += 1;
+        self.entry_count += 1;
+    }
+}
+
+/// One page of entry addresses. Slot `i` holds entry id
+/// `index * ENTRIES_PER_PAGE + i`; deleted entries leave `Pubkey::default()`
+/// behind so the remaining ids keep their slots.
+#[account]
+#[derive(InitSpace)]
+pub struct JournalPage {
+    pub owner: Pubkey,
+    pub index: u64,
+    #[max_len(ENTRIES_PER_PAGE)]
+    pub entries: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl JournalPage {
+    /// Clears the slot holding `entry`, returning `false` if it isn't on this page.
+    pub fn remove(&mut self, entry: &Pubkey) -> bool {
+        match self.entries.iter_mut().find(|slot| *slot == entry) {
+            Some(slot) => {
+                *slot = Pubkey::default();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Points the slot holding `old` at `new`, returning `false` if `old` isn't
+    /// on this page.
+    pub fn replace(&mut self, old: &Pubkey, new: Pubkey) -> bool {
+        match self.entries.iter_mut().find(|slot| *slot == old) {
+            Some(slot) => {
+                *slot = new;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A named collection of entries with its own title namespace; see
+/// `entry_seed`.
+#[account]
+#[derive(InitSpace)]
+pub struct Notebook {
+    pub owner: Pubkey,
+    pub id: u32,
+    #[max_len(NOTEBOOK_NAME_LENGTH)]
+    pub name: String,
+    #[max_len(DESCRIPTION_LENGTH)]
+    pub description: String,
+    pub entry_count: u64,
+    pub bump: u8,
+}
+
+/// The owner's entries carrying `tag`, in the order they were tagged. Sized
+/// to its content and closed once it lists no entries.
+#[account]
+#[derive(InitSpace)]
+pub struct TagIndex {
+    pub owner: Pubkey,
+    #[max_len(TAG_LENGTH)]
+    pub tag: String,
+    #[max_len(MAX_TAGGED_ENTRIES)]
+    pub entries: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl TagIndex {
+    /// Account size for an index of `tag_len` bytes listing `entry_count` entries.
+    pub fn space(tag_len: usize, entry_count: usize) -> usize {
+        8 + TagIndex::INIT_SPACE - TAG_LENGTH - 32 * MAX_TAGGED_ENTRIES + tag_len + 32 * entry_count
+    }
+}
+
+/// Global index of public entries across all owners, paginated the same way
+/// as `UserJournal`.
+#[account]
+#[derive(InitSpace)]
+pub struct PublicFeed {
+    pub entry_count: u64,
+    pub next_entry_id: u64,
+    pub bump: u8,
+}
+
+impl PublicFeed {
+    /// Page that receives the next published entry.
+    pub fn current_page(&self) -> u64 {
+        self.next_entry_id / ENTRIES_PER_PAGE as u64
+    }
+
+    /// Lists `entry` on `page`, which must be the page returned by
+    /// `current_page`.
+    pub fn push_entry(&mut self, page: &mut PublicFeedPage, entry: Pubkey) {
+        page.entries.push(entry);
+        self.next_entry_id += 1;
+        self.entry_count += 1;
+    }
+}
+
+/// One page of public entry addresses; unpublished entries leave
+/// `Pubkey::default()` behind, like in a `JournalPage`.
+#[account]
+#[derive(InitSpace)]
+pub struct PublicFeedPage {
+    pub index: u64,
+    #[max_len(ENTRIES_PER_PAGE)]
+    pub entries: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl PublicFeedPage {
+    /// Clears the slot holding `entry`, returning `false` if it isn't on this page.
+    pub fn remove(&mut self, entry: &Pubkey) -> bool {
+        match self.entries.iter_mut().find(|slot| *slot == entry) {
+            Some(slot) => {
+                *slot = Pubkey::default();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A superseded message of an entry in history mode, keyed by the revision
+/// number it had on the entry.
+#[account]
+#[derive(InitSpace)]
+pub struct JournalRevision {
+    pub entry: Pubkey,
+    pub revision: u32,
+    #[max_len(MESSAGE_LENGTH)]
+    pub message: String,
+    pub recorded_at: i64,
+    /// `history_hash` of the entry before this revision was recorded.
+    pub prev_hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl JournalRevision {
+    /// Account size for a revision holding exactly this message.
+    pub fn space(message_len: usize) -> usize {
+        8 + JournalRevision::INIT_SPACE - MESSAGE_LENGTH + message_len
+    }
+
+    /// Link of the history chain: the entry's `history_hash` right after this
+    /// revision was recorded.
+    pub fn chain_hash(&self) -> [u8; 32] {
+        hashv(&[
+            &self.prev_hash,
+            &self.revision.to_le_bytes(),
+            &self.recorded_at.to_le_bytes(),
+            self.message.as_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+/// A page of a long-form entry. Pages are addressed by an id that is never
+/// reused; their order is kept in `JournalEntry::pages`.
+#[account]
+#[derive(InitSpace)]
+pub struct EntryPage {
+    pub entry: Pubkey,
+    pub page_id: u16,
+    #[max_len(PAGE_LENGTH)]
+    pub content: String,
+    pub bump: u8,
+}
+
+impl EntryPage {
+    /// Account size for a page holding exactly this content.
+    pub fn space(content_len: usize) -> usize {
+        8 + EntryPage::INIT_SPACE - PAGE_LENGTH + content_len
+    }
+}
+
+/// Rights that `owner` has granted `delegate` on every entry of their journal.
+#[account]
+#[derive(InitSpace)]
+pub struct JournalDelegate {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    /// Bitwise OR of the `DELEGATE_*` permissions.
+    pub permissions: u8,
+    /// Unix timestamp from which the grant no longer applies.
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl JournalDelegate {
+    /// Whether `authority` may act on `owner`'s journal with any of
+    /// `permissions`: it is either the owner itself or holds an unexpired
+    /// `grant` carrying one of them.
+    pub fn authorizes(
+        grant: Option<&JournalDelegate>,
+        owner: &Pubkey,
+        authority: &Pubkey,
+        permissions: u8,
+    ) -> Result<bool> {
+        if authority == owner {
+            return Ok(true);
+        }
+        let Some(grant) = grant else {
+            return Ok(false);
+        };
+        if let Some(expires_at) = grant.expires_at {
+            if Clock::get()?.unix_timestamp >= expires_at {
+                return err!(JournalError::DelegationExpired);
+            }
+        }
+        Ok(grant.permissions & permissions != 0)
+    }
+}
+
+/// Pending handover of `entry` to `recipient`. Only one offer per entry can
+/// be open; it ends with `accept_transfer` or `cancel_transfer`, or when the
+/// entry is deleted.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferOffer {
+    pub entry: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub offered_at: i64,
+    /// `created_at` of the offered entry, so that the offer cannot be
+    /// accepted for a later entry that reuses the address.
+    pub entry_created_at: i64,
+    pub bump: u8,
+}
@@ -1,32 +1,113 @@
 import * as web3 from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
+import { createHash } from "crypto";
 import type { JournalDapp } from "../target/types/journal_dapp";
+
 describe("journal_dapp", () => {
   // Configure the client to use the local cluster
   anchor.setProvider(anchor.AnchorProvider.env());
 
-  const program = anchor.workspace.JournalDapp as anchor.Program<JournalDapp>;
-  
   // 1. Setup
-  const program = program;
-  const wallet = pg.wallet;
+  const program = anchor.workspace.JournalDapp as anchor.Program<JournalDapp>;
+  const wallet = (program.provider as anchor.AnchorProvider).wallet;
 
   // 2. Test Data
   const title = "Final Submission";
   const message = "Testing all requirements";
 
-  it("1. Happy Path: Create Entry", async () => {
-    // Generate PDA
-    const [pda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from(title), wallet.publicKey.toBuffer()],
+  // Entries are seeded with the sha256 of the trimmed, lower-cased title
+  const entryPda = (entryTitle: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("JOURNAL_ENTRY"),
+        createHash("sha256").update(entryTitle.trim().toLowerCase()).digest(),
+        wallet.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+  const [userJournalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("USER_JOURNAL"), wallet.publicKey.toBuffer()],
+    program.programId
+  );
+  const journalPagePda = (index: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("JOURNAL_PAGE"),
+        wallet.publicKey.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+  const revisionPda = (entry: web3.PublicKey, revision: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("JOURNAL_REVISION"),
+        entry.toBuffer(),
+        new anchor.BN(revision).toArrayLike(Buffer, "le", 4),
+      ],
+      program.programId
+    );
+  const entryPagePda = (entry: web3.PublicKey, pageId: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ENTRY_PAGE"), entry.toBuffer(), new anchor.BN(pageId).toArrayLike(Buffer, "le", 2)],
+      program.programId
+    );
+  const readerPda = (entry: web3.PublicKey, reader: web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ENTRY_READER"), entry.toBuffer(), reader.toBuffer()],
+      program.programId
+    );
+  const [publicFeedPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("PUBLIC_FEED")],
+    program.programId
+  );
+  const feedPagePda = (index: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PUBLIC_FEED_PAGE"), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
       program.programId
     );
+  const notebookPda = (id: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("NOTEBOOK"), wallet.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+  // Entries inside a notebook hash the notebook id in front of the title
+  const notebookEntryPda = (id: number, entryTitle: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("JOURNAL_ENTRY"),
+        createHash("sha256")
+          .update(new anchor.BN(id).toArrayLike(Buffer, "le", 4))
+          .update(entryTitle.trim().toLowerCase())
+          .digest(),
+        wallet.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+  const transferPda = (entry: web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("TRANSFER_OFFER"), entry.toBuffer()], program.programId);
+  const tagIndexPda = (tag: string) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("TAG_INDEX"), wallet.publicKey.toBuffer(), Buffer.from(tag)],
+      program.programId
+    );
+  // Page that the next created entry will be listed on
+  const currentPagePda = async () => {
+    const journal = await program.account.userJournal.fetchNullable(userJournalPda);
+    return journalPagePda(journal ? Math.floor(journal.nextEntryId.toNumber() / 32) : 0)[0];
+  };
+
+  it("1. Happy Path: Create Entry", async () => {
+    // Generate PDA
+    const [pda] = entryPda(title);
 
     // Call Instruction
     await program.methods
-      .createEntry(title, message)
+      .createEntry(title, message, [])
       .accounts({
         journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
         owner: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -39,16 +120,15 @@ describe("journal_dapp", () => {
   });
 
   it("2. Unhappy Path: Prevent Duplicate", async () => {
-    const [pda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from(title), wallet.publicKey.toBuffer()],
-      program.programId
-    );
+    const [pda] = entryPda(title);
 
     try {
       await program.methods
-        .createEntry(title, "Duplicate")
+        .createEntry(title, "Duplicate", [])
         .accounts({
           journalEntry: pda,
+          userJournal: userJournalPda,
+          journalPage: await currentPagePda(),
           owner: wallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -64,38 +144,42 @@ describe("journal_dapp", () => {
   });
 
   it("3. Happy Path: Update Entry", async () => {
-    const [pda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from(title), wallet.publicKey.toBuffer()],
-      program.programId
-    );
+    const [pda] = entryPda(title);
 
     const newMessage = "Updated Content";
 
     await program.methods
-      .updateEntry(title, newMessage)
+      .updateEntry(title, newMessage, null)
       .accounts({
         journalEntry: pda,
+        journalRevision: null,
+        journalDelegate: null,
         owner: wallet.publicKey,
+        authority: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
 
     const account = await program.account.journalEntry.fetch(pda);
     if (account.message !== newMessage) throw new Error("Update failed");
+    if (account.revision !== 1) throw new Error("Revision not bumped");
+    if (account.updatedAt.lt(account.createdAt)) throw new Error("Timestamps out of order");
     console.log("✅ Update Passed");
   });
 
   it("4. Happy Path: Delete Entry", async () => {
-    const [pda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from(title), wallet.publicKey.toBuffer()],
-      program.programId
-    );
+    const [pda] = entryPda(title);
 
     await program.methods
       .deleteEntry(title)
       .accounts({
         journalEntry: pda,
+        userJournal: userJournalPda,
+        transferOffer: transferPda(pda)[0],
+        journalPage: journalPagePda(0)[0],
+        journalDelegate: null,
         owner: wallet.publicKey,
+        authority: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -108,4 +192,1014 @@ describe("journal_dapp", () => {
       console.log("✅ Delete Passed");
     }
   });
+
+  it("5. Happy Path: Title Longer Than 32 Bytes", async () => {
+    const longTitle = "A perfectly ordinary title over 32 bytes";
+    const [pda] = entryPda(longTitle);
+
+    await program.methods
+      .createEntry(longTitle, message, [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.title !== longTitle) throw new Error("Title mismatch");
+    console.log("✅ Long Title Passed");
+  });
+
+  it("6. Happy Path: List Entries From The Index", async () => {
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    const listed = [];
+    for (let i = 0; i < Math.ceil(journal.nextEntryId.toNumber() / 32); i++) {
+      const page = await program.account.journalPage.fetch(journalPagePda(i)[0]);
+      listed.push(...page.entries.filter((e) => !e.equals(web3.PublicKey.default)));
+    }
+
+    if (listed.length !== journal.entryCount.toNumber()) throw new Error("Index out of sync");
+    if (!listed.some((e) => e.equals(entryPda("A perfectly ordinary title over 32 bytes")[0])))
+      throw new Error("Entry missing from index");
+    console.log("✅ Index Listing Passed");
+  });
+
+  it("7. Happy Path: History Mode Keeps Revisions", async () => {
+    const historyTitle = "Audit log";
+    const [pda] = entryPda(historyTitle);
+
+    await program.methods
+      .createEntry(historyTitle, "v0", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .setHistoryMode(historyTitle, true)
+      .accounts({ journalEntry: pda, owner: wallet.publicKey })
+      .rpc();
+    await program.methods
+      .updateEntry(historyTitle, "v1", null)
+      .accounts({
+        journalEntry: pda,
+        journalRevision: revisionPda(pda, 0)[0],
+        journalDelegate: null,
+        owner: wallet.publicKey,
+        authority: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const revision = await program.account.journalRevision.fetch(revisionPda(pda, 0)[0]);
+    if (revision.message !== "v0") throw new Error("Old message not kept");
+
+    const read = await program.methods
+      .readRevision(historyTitle, 0)
+      .accounts({
+        journalEntry: pda,
+        journalRevision: revisionPda(pda, 0)[0],
+        owner: wallet.publicKey,
+      })
+      .view();
+    if (read !== "v0") throw new Error("Read returned the wrong message");
+
+    await program.methods
+      .restoreRevision(historyTitle, 0)
+      .accounts({
+        journalEntry: pda,
+        restoredRevision: revisionPda(pda, 0)[0],
+        journalRevision: revisionPda(pda, 1)[0],
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    let account = await program.account.journalEntry.fetch(pda);
+    if (account.message !== "v0") throw new Error("Restore failed");
+    if (account.historyLen !== 2) throw new Error("Restore did not keep v1");
+
+    await program.methods
+      .pruneRevision(historyTitle, 0)
+      .accounts({
+        journalEntry: pda,
+        journalRevision: revisionPda(pda, 0)[0],
+        owner: wallet.publicKey,
+      })
+      .rpc();
+    account = await program.account.journalEntry.fetch(pda);
+    if (account.historyLen !== 1) throw new Error("Prune failed");
+    console.log("✅ History Mode Passed");
+  });
+
+  it("8. Happy Path: Account Size Follows Content", async () => {
+    const noteTitle = "Short note";
+    const [pda] = entryPda(noteTitle);
+    const size = async () => (await program.provider.connection.getAccountInfo(pda)).data.length;
+
+    await program.methods
+      .createEntry(noteTitle, "hi", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const created = await size();
+    if (created > 200) throw new Error("Short note allocated at full size");
+
+    const update = (message: string) =>
+      program.methods
+        .updateEntry(noteTitle, message, null)
+        .accounts({
+          journalEntry: pda,
+          journalRevision: null,
+          journalDelegate: null,
+          owner: wallet.publicKey,
+          authority: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    await update("hi".repeat(200));
+    if ((await size()) !== created + 398) throw new Error("Account did not grow");
+    await update("bye");
+    if ((await size()) !== created + 1) throw new Error("Account did not shrink");
+    console.log("✅ Realloc Passed");
+  });
+
+  it("9. Happy Path: Long-Form Entry Pages", async () => {
+    const longTitle = "Chapter one";
+    const [pda] = entryPda(longTitle);
+
+    await program.methods
+      .createEntry(longTitle, "It was a dark and stormy night", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    for (const [pageId, content] of ["; the rain", " fell in torrents"].entries()) {
+      await program.methods
+        .appendPage(longTitle, content)
+        .accounts({
+          journalEntry: pda,
+          entryPage: entryPagePda(pda, pageId)[0],
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await program.methods
+      .replacePage(longTitle, 1, " kept falling")
+      .accounts({
+        journalEntry: pda,
+        entryPage: entryPagePda(pda, 1)[0],
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .removePage(longTitle, 0)
+      .accounts({
+        journalEntry: pda,
+        entryPage: entryPagePda(pda, 0)[0],
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.pages.length !== 1 || account.pages[0] !== 1) throw new Error("Page order wrong");
+    const page = await program.account.entryPage.fetch(entryPagePda(pda, 1)[0]);
+    if (page.content !== " kept falling") throw new Error("Replace failed");
+
+    // Deleting the entry closes its remaining pages in the same transaction
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    await program.methods
+      .deleteEntry(longTitle)
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        transferOffer: transferPda(pda)[0],
+        journalPage: journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0],
+        journalDelegate: null,
+        owner: wallet.publicKey,
+        authority: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: entryPagePda(pda, 1)[0], isSigner: false, isWritable: true }])
+      .rpc();
+    if (await program.account.entryPage.fetchNullable(entryPagePda(pda, 1)[0]))
+      throw new Error("Page outlived its entry");
+    console.log("✅ Long-Form Pages Passed");
+  });
+
+  it("10. Encrypted Entries Store Ciphertext Only", async () => {
+    const secretTitle = "Secret";
+    const [pda] = entryPda(secretTitle);
+    const content = (len: number) => ({
+      scheme: { xChaCha20Poly1305: {} },
+      nonce: Array.from(web3.Keypair.generate().secretKey.slice(0, 24)),
+      ciphertext: Buffer.alloc(len, 7),
+    });
+    const create = async (len: number) =>
+      program.methods
+        .createEncryptedEntry(secretTitle, content(len))
+        .accounts({
+          journalEntry: pda,
+          userJournal: userJournalPda,
+          journalPage: await currentPagePda(),
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    try {
+      await create(15);
+      throw new Error("Accepted a ciphertext without a tag");
+    } catch (e) {
+      if (e.message === "Accepted a ciphertext without a tag") throw e;
+    }
+    await create(64);
+
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.message !== "") throw new Error("Plaintext stored");
+    if (account.encrypted.ciphertext.length !== 64) throw new Error("Ciphertext mismatch");
+    console.log("✅ Encrypted Entry Passed");
+  });
+
+  it("11. Shared Entries Grant And Revoke Readers", async () => {
+    const sharedTitle = "Shared secret";
+    const [pda] = entryPda(sharedTitle);
+    const reader = web3.Keypair.generate().publicKey;
+    const [grant] = readerPda(pda, reader);
+
+    await program.methods
+      .createEncryptedEntry(sharedTitle, {
+        scheme: { xChaCha20Poly1305Envelope: {} },
+        nonce: Array(24).fill(1),
+        ciphertext: Buffer.alloc(32, 7),
+      })
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .grantReader(sharedTitle, reader, Array(104).fill(9))
+      .accounts({
+        journalEntry: pda,
+        entryReader: grant,
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const entryReader = await program.account.entryReader.fetch(grant);
+    if (!entryReader.reader.equals(reader)) throw new Error("Grant stored wrong reader");
+    if ((await program.account.journalEntry.fetch(pda)).readerCount !== 1)
+      throw new Error("Reader not counted");
+
+    await program.methods
+      .revokeReader(sharedTitle, reader)
+      .accounts({ journalEntry: pda, entryReader: grant, owner: wallet.publicKey })
+      .rpc();
+    if (await program.account.entryReader.fetchNullable(grant)) throw new Error("Grant outlived revoke");
+    console.log("✅ Shared Entry Passed");
+  });
+
+  it("12. Delegates Append But Cannot Rewrite Or Delete", async () => {
+    const logTitle = "Ops log";
+    const [pda] = entryPda(logTitle);
+    const coAuthor = web3.Keypair.generate();
+    const [grant] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("JOURNAL_DELEGATE"), wallet.publicKey.toBuffer(), coAuthor.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.provider.connection.confirmTransaction(
+      await program.provider.connection.requestAirdrop(coAuthor.publicKey, web3.LAMPORTS_PER_SOL)
+    );
+
+    await program.methods
+      .createEntry(logTitle, "09:00 deploy started", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .setDelegate(coAuthor.publicKey, 1, null)
+      .accounts({
+        journalDelegate: grant,
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const update = (newMessage: string) =>
+      program.methods
+        .updateEntry(logTitle, newMessage, null)
+        .accounts({
+          journalEntry: pda,
+          journalRevision: null,
+          journalDelegate: grant,
+          owner: wallet.publicKey,
+          authority: coAuthor.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([coAuthor])
+        .rpc();
+    await update("09:00 deploy started\n09:05 deploy finished");
+    try {
+      await update("nothing happened");
+      throw new Error("Append-only delegate rewrote the entry");
+    } catch (e) {
+      if (e.message === "Append-only delegate rewrote the entry") throw e;
+    }
+
+    const account = await program.account.journalEntry.fetch(pda);
+    if (!account.message.endsWith("09:05 deploy finished")) throw new Error("Append lost");
+    console.log("✅ Delegation Passed");
+  });
+
+  it("13. Unhappy Path: Invalid Titles And Messages", async () => {
+    const expectError = async (entryTitle: string, entryMessage: string, code: string) => {
+      try {
+        await program.methods
+          .createEntry(entryTitle, entryMessage, [])
+          .accounts({
+            journalEntry: entryPda(entryTitle)[0],
+            userJournal: userJournalPda,
+            journalPage: await currentPagePda(),
+            owner: wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+      } catch (e) {
+        if (e.error?.errorCode?.code !== code) throw new Error(`Expected ${code}, got ${e}`);
+        return;
+      }
+      throw new Error(`Expected ${code}`);
+    };
+
+    await expectError("   ", message, "TitleEmpty");
+    await expectError("Tab\there", message, "TitleControlCharacter");
+    await expectError("x".repeat(51), message, "TitleTooLong");
+    await expectError("é".repeat(30), message, "TitleTooLongUtf8");
+    console.log("✅ Validation Passed");
+  });
+
+  it("14. Instructions Emit Events", async () => {
+    const eventTitle = "Observed";
+    const [pda] = entryPda(eventTitle);
+    let created = null;
+    const listener = program.addEventListener("EntryCreated", (event) => (created = event));
+
+    await program.methods
+      .createEntry(eventTitle, message, [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    if (!created || !created.entry.equals(pda)) throw new Error("EntryCreated not emitted");
+    if (created.contentLen !== message.length) throw new Error("Wrong content length");
+    console.log("✅ Events Passed");
+  });
+
+  it("15. Rename Moves The Entry And Keeps Its Metadata", async () => {
+    const [oldPda] = entryPda("Shoping list");
+    const [newPda] = entryPda("Shopping list");
+
+    await program.methods
+      .createEntry("Shoping list", "milk, eggs", [])
+      .accounts({
+        journalEntry: oldPda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const before = await program.account.journalEntry.fetch(oldPda);
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    await program.methods
+      .renameEntry("Shoping list", "Shopping list")
+      .accounts({
+        journalEntry: oldPda,
+        newEntry: newPda,
+        transferOffer: transferPda(oldPda)[0],
+        journalPage: journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0],
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    if (await program.account.journalEntry.fetchNullable(oldPda)) throw new Error("Old entry left behind");
+    const after = await program.account.journalEntry.fetch(newPda);
+    if (after.title !== "Shopping list") throw new Error("Title not changed");
+    if (!after.createdAt.eq(before.createdAt)) throw new Error("Creation time lost");
+
+    try {
+      await program.methods
+        .renameEntry("Shopping list", "Observed")
+        .accounts({
+          journalEntry: newPda,
+          newEntry: entryPda("Observed")[0],
+          transferOffer: transferPda(newPda)[0],
+          journalPage: journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0],
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Renamed onto an existing title");
+    } catch (e) {
+      if (e.message === "Renamed onto an existing title") throw e;
+    }
+    console.log("✅ Rename Passed");
+  });
+
+  it("16. Archive, Restore And Purge", async () => {
+    const archiveTitle = "Old plans";
+    const [pda] = entryPda(archiveTitle);
+    await program.methods
+      .createEntry(archiveTitle, "Move to Mars", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    const page = journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0];
+    const archive = (gracePeriod: number | null) =>
+      program.methods
+        .archiveEntry(archiveTitle, gracePeriod)
+        .accounts({ journalEntry: pda, owner: wallet.publicKey })
+        .rpc();
+    const purge = () =>
+      program.methods
+        .purgeEntry(archiveTitle)
+        .accounts({
+          journalEntry: pda,
+          userJournal: userJournalPda,
+          transferOffer: transferPda(pda)[0],
+          journalPage: page,
+          owner: wallet.publicKey,
+        })
+        .rpc();
+
+    await archive(3600);
+    if (!(await program.account.journalEntry.fetch(pda)).archivedAt) throw new Error("Not archived");
+    try {
+      await purge();
+      throw new Error("Purged during the grace period");
+    } catch (e) {
+      if (e.message === "Purged during the grace period") throw e;
+    }
+
+    await program.methods
+      .restoreEntry(archiveTitle)
+      .accounts({ journalEntry: pda, owner: wallet.publicKey })
+      .rpc();
+    if ((await program.account.journalEntry.fetch(pda)).archivedAt) throw new Error("Not restored");
+
+    await archive(null);
+    await purge();
+    if (await program.account.journalEntry.fetchNullable(pda)) throw new Error("Not purged");
+    console.log("✅ Archive Passed");
+  });
+
+  it("17. Public Entries Appear In The Feed", async () => {
+    const publicTitle = "Open letter";
+    const [pda] = entryPda(publicTitle);
+    await program.methods
+      .createEntry(publicTitle, "Hello world", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const feed = await program.account.publicFeed.fetchNullable(publicFeedPda);
+    const page = feedPagePda(feed ? Math.floor(feed.nextEntryId.toNumber() / 32) : 0)[0];
+    const setVisibility = (visibility) =>
+      program.methods
+        .setVisibility(publicTitle, visibility)
+        .accounts({
+          journalEntry: pda,
+          publicFeed: publicFeedPda,
+          feedPage: page,
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    await setVisibility({ public: {} });
+    const listed = await program.account.publicFeedPage.fetch(page);
+    if (!listed.entries.some((entry) => entry.equals(pda))) throw new Error("Entry not in feed");
+
+    await setVisibility({ unlisted: {} });
+    const unlisted = await program.account.publicFeedPage.fetch(page);
+    if (unlisted.entries.some((entry) => entry.equals(pda))) throw new Error("Entry still in feed");
+    const account = await program.account.journalEntry.fetch(pda);
+    if (!account.visibility.unlisted || account.feedPage !== null) throw new Error("Visibility not stored");
+    console.log("✅ Public Feed Passed");
+  });
+
+  it("18. Notebooks Have Their Own Title Namespace", async () => {
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    const notebookId = journal.lastNotebookId + 1;
+    const [notebook] = notebookPda(notebookId);
+    await program.methods
+      .createNotebook("Work", "Things for the office")
+      .accounts({
+        userJournal: userJournalPda,
+        notebook,
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // The same title can exist once per notebook
+    const [workEntry] = notebookEntryPda(notebookId, "Standup");
+    for (const [journalEntry, inNotebook] of [
+      [workEntry, notebook],
+      [entryPda("Standup")[0], null],
+    ]) {
+      await program.methods
+        .createEntry("Standup", "Ship the release", [])
+        .accounts({
+          journalEntry,
+          userJournal: userJournalPda,
+          journalPage: await currentPagePda(),
+          notebook: inNotebook,
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+    if ((await program.account.notebook.fetch(notebook)).entryCount.toNumber() !== 1)
+      throw new Error("Notebook did not count its entry");
+
+    try {
+      await program.methods
+        .closeNotebook(notebookId)
+        .accounts({ notebook, owner: wallet.publicKey })
+        .rpc();
+      throw new Error("Closed a notebook holding entries");
+    } catch (e) {
+      if (e.message === "Closed a notebook holding entries") throw e;
+    }
+
+    // Moving the notebook entry out collides with the root entry
+    const after = await program.account.userJournal.fetch(userJournalPda);
+    try {
+      await program.methods
+        .moveEntry("Standup", 0)
+        .accounts({
+          journalEntry: workEntry,
+          newEntry: entryPda("Standup")[0],
+          transferOffer: transferPda(workEntry)[0],
+          fromNotebook: notebook,
+          toNotebook: null,
+          journalPage: journalPagePda(Math.floor((after.nextEntryId.toNumber() - 2) / 32))[0],
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Moved onto an existing title");
+    } catch (e) {
+      if (e.message === "Moved onto an existing title") throw e;
+    }
+
+    await program.methods
+      .renameNotebook(notebookId, "Office")
+      .accounts({ notebook, owner: wallet.publicKey })
+      .rpc();
+    if ((await program.account.notebook.fetch(notebook)).name !== "Office") throw new Error("Rename failed");
+    console.log("✅ Notebooks Passed");
+  });
+
+  it("19. Tags Keep Their Indexes In Sync", async () => {
+    const tagTitle = "Trip ideas";
+    const [pda] = entryPda(tagTitle);
+    const asRemaining = (tags: string[]) =>
+      tags.map((tag) => ({ pubkey: tagIndexPda(tag)[0], isWritable: true, isSigner: false }));
+    await program.methods
+      .createEntry(tagTitle, "Lisbon in spring", ["travel", "todo"])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(asRemaining(["travel", "todo"]))
+      .rpc();
+    const travel = await program.account.tagIndex.fetch(tagIndexPda("travel")[0]);
+    if (!travel.entries.some((entry) => entry.equals(pda))) throw new Error("Entry not indexed");
+
+    try {
+      await program.methods
+        .createEntry("Bad tags", "x", ["Travel"])
+        .accounts({
+          journalEntry: entryPda("Bad tags")[0],
+          userJournal: userJournalPda,
+          journalPage: await currentPagePda(),
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(asRemaining(["Travel"]))
+        .rpc();
+      throw new Error("Accepted an uppercase tag");
+    } catch (e) {
+      if (e.message === "Accepted an uppercase tag") throw e;
+    }
+
+    // Dropping "todo" and adding "2025": removed indexes first, then added ones
+    await program.methods
+      .updateEntry(tagTitle, "Lisbon in spring", ["travel", "2025"])
+      .accounts({
+        journalEntry: pda,
+        journalRevision: null,
+        journalDelegate: null,
+        owner: wallet.publicKey,
+        authority: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(asRemaining(["todo", "2025"]))
+      .rpc();
+    if (await program.account.tagIndex.fetchNullable(tagIndexPda("todo")[0]))
+      throw new Error("Empty tag index not closed");
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.tags.join(",") !== "travel,2025") throw new Error("Tags not stored");
+
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    await program.methods
+      .deleteEntry(tagTitle)
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        transferOffer: transferPda(pda)[0],
+        journalPage: journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0],
+        journalDelegate: null,
+        owner: wallet.publicKey,
+        authority: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(asRemaining(["travel", "2025"]))
+      .rpc();
+    if (await program.account.tagIndex.fetchNullable(tagIndexPda("travel")[0]))
+      throw new Error("Tag index outlived its entries");
+    console.log("✅ Tags Passed");
+  });
+
+  it("20. Append And Patch Edit The Message In Place", async () => {
+    const logTitle = "Daily log";
+    const [pda] = entryPda(logTitle);
+    await program.methods
+      .createEntry(logTitle, "08:00 coffee", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const editAccounts = {
+      journalEntry: pda,
+      journalRevision: null,
+      journalDelegate: null,
+      owner: wallet.publicKey,
+      authority: wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+
+    await program.methods.appendToEntry(logTitle, "\n09:30 standup").accounts(editAccounts).rpc();
+    await program.methods.patchEntry(logTitle, 6, 6, "tea ☕").accounts(editAccounts).rpc();
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.message !== "08:00 tea ☕\n09:30 standup") throw new Error("Edits not applied");
+    if (account.revision !== 2) throw new Error("Revision not bumped");
+
+    // "☕" is 3 bytes, so offset 11 lands inside it
+    try {
+      await program.methods.patchEntry(logTitle, 11, 1, "x").accounts(editAccounts).rpc();
+      throw new Error("Split a character");
+    } catch (e) {
+      if (e.message === "Split a character") throw e;
+    }
+    try {
+      await program.methods.patchEntry(logTitle, 0, 1000, "").accounts(editAccounts).rpc();
+      throw new Error("Patched past the end");
+    } catch (e) {
+      if (e.message === "Patched past the end") throw e;
+    }
+    console.log("✅ Append And Patch Passed");
+  });
+
+  it("21. Sealed Entries Are Write-Once", async () => {
+    const auditTitle = "Audit 2025-Q1";
+    const auditMessage = "Books reviewed, no findings";
+    const [pda] = entryPda(auditTitle);
+    await program.methods
+      .createEntry(auditTitle, auditMessage, [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .sealEntry(auditTitle)
+      .accounts({ journalEntry: pda, owner: wallet.publicKey })
+      .rpc();
+
+    // Anyone can check the content against the recorded hash
+    const { seal } = await program.account.journalEntry.fetch(pda);
+    const expected = createHash("sha256").update(auditMessage).digest();
+    if (!seal || !Buffer.from(seal.contentHash).equals(expected)) throw new Error("Seal hash mismatch");
+
+    try {
+      await program.methods
+        .updateEntry(auditTitle, "Books reviewed, one finding", null)
+        .accounts({
+          journalEntry: pda,
+          journalRevision: null,
+          journalDelegate: null,
+          owner: wallet.publicKey,
+          authority: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Updated a sealed entry");
+    } catch (e) {
+      if (e.message === "Updated a sealed entry") throw e;
+      if (e.error?.errorCode?.code !== "EntrySealed") throw e;
+    }
+    try {
+      await program.methods
+        .archiveEntry(auditTitle, null)
+        .accounts({ journalEntry: pda, owner: wallet.publicKey })
+        .rpc();
+      throw new Error("Archived a sealed entry");
+    } catch (e) {
+      if (e.message === "Archived a sealed entry") throw e;
+    }
+    console.log("✅ Seal Passed");
+  });
+
+  it("22. Notarized Documents Store Only Their Hash", async () => {
+    const docTitle = "Lease agreement";
+    const [pda] = entryPda(docTitle);
+    const sha256 = createHash("sha256").update("signed lease, page 1 of 1").digest();
+    await program.methods
+      .notarize(docTitle, [...sha256], "ipfs://lease.pdf", "application/pdf")
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        notebook: null,
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const account = await program.account.journalEntry.fetch(pda);
+    if (account.message !== "") throw new Error("Notarized entry has a message");
+    if (!Buffer.from(account.notarization.sha256).equals(sha256)) throw new Error("Hash not stored");
+    if (account.notarization.mime !== "application/pdf") throw new Error("Metadata not stored");
+    if (!account.seal || !Buffer.from(account.seal.contentHash).equals(sha256))
+      throw new Error("Notarized entry not sealed");
+    console.log("✅ Notarize Passed");
+  });
+
+  it("23. Entries Can Be Handed Over To Another Wallet", async () => {
+    const handoverTitle = "Runbook";
+    const [pda] = entryPda(handoverTitle);
+    const successor = web3.Keypair.generate();
+    await program.provider.connection.confirmTransaction(
+      await program.provider.connection.requestAirdrop(successor.publicKey, web3.LAMPORTS_PER_SOL)
+    );
+    const pdaOf = (seeds: Buffer[]) => anchor.web3.PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const [newEntry] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("JOURNAL_ENTRY"),
+        createHash("sha256").update(handoverTitle.toLowerCase()).digest(),
+        successor.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const successorJournal = pdaOf([Buffer.from("USER_JOURNAL"), successor.publicKey.toBuffer()]);
+    const successorPage = pdaOf([
+      Buffer.from("JOURNAL_PAGE"),
+      successor.publicKey.toBuffer(),
+      new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+    ]);
+    const offer = pdaOf([Buffer.from("TRANSFER_OFFER"), pda.toBuffer()]);
+
+    await program.methods
+      .createEntry(handoverTitle, "Restart the indexer first", [])
+      .accounts({
+        journalEntry: pda,
+        userJournal: userJournalPda,
+        journalPage: await currentPagePda(),
+        owner: wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    const previousPage = journalPagePda(Math.floor((journal.nextEntryId.toNumber() - 1) / 32))[0];
+    const offerTo = () =>
+      program.methods
+        .offerTransfer(handoverTitle, successor.publicKey)
+        .accounts({ journalEntry: pda, transferOffer: offer, owner: wallet.publicKey })
+        .rpc();
+
+    // The offerer can withdraw before it is accepted
+    await offerTo();
+    await program.methods
+      .cancelTransfer(pda)
+      .accounts({ transferOffer: offer, owner: wallet.publicKey })
+      .rpc();
+    if (await program.account.transferOffer.fetchNullable(offer)) throw new Error("Offer not cancelled");
+
+    await offerTo();
+    await program.methods
+      .acceptTransfer(handoverTitle)
+      .accounts({
+        journalEntry: pda,
+        transferOffer: offer,
+        newEntry,
+        previousJournal: userJournalPda,
+        previousPage,
+        notebook: null,
+        userJournal: successorJournal,
+        journalPage: successorPage,
+        previousOwner: wallet.publicKey,
+        recipient: successor.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([successor])
+      .rpc();
+
+    if (await program.account.journalEntry.fetchNullable(pda)) throw new Error("Old entry still exists");
+    const moved = await program.account.journalEntry.fetch(newEntry);
+    if (!moved.owner.equals(successor.publicKey)) throw new Error("Owner not updated");
+    const page = await program.account.journalPage.fetch(successorPage);
+    if (!page.entries.some((entry) => entry.equals(newEntry))) throw new Error("Not in the recipient's index");
+    console.log("✅ Transfer Passed");
+  });
+
+  it("24. Batches Create And Delete Entries All Or Nothing", async () => {
+    const batch = ["Batch one", "Batch two", "Batch three"].map((title) => ({ title, message: `${title} body` }));
+    const writable = (pubkey: web3.PublicKey) => ({ pubkey, isWritable: true, isSigner: false });
+    const journal = await program.account.userJournal.fetch(userJournalPda);
+    const firstId = journal.nextEntryId.toNumber();
+    const pagesFor = (count: number) => {
+      const pages = [];
+      for (let page = Math.floor(firstId / 32); page <= Math.floor((firstId + count - 1) / 32); page++)
+        pages.push(journalPagePda(page)[0]);
+      return pages;
+    };
+    const createBatch = (entries) =>
+      program.methods
+        .createEntries(entries)
+        .accounts({
+          userJournal: userJournalPda,
+          owner: wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          ...entries.map(({ title }) => writable(entryPda(title)[0])),
+          ...pagesFor(entries.length).map(writable),
+        ])
+        .rpc();
+
+    // A repeated title sinks the whole batch
+    try {
+      await createBatch([...batch, batch[0]]);
+      throw new Error("Created a batch with a repeated title");
+    } catch (e) {
+      if (e.message === "Created a batch with a repeated title") throw e;
+    }
+    if (await program.account.journalEntry.fetchNullable(entryPda(batch[1].title)[0]))
+      throw new Error("Partial batch was applied");
+
+    await createBatch(batch);
+    for (const { title, message: body } of batch) {
+      const account = await program.account.journalEntry.fetch(entryPda(title)[0]);
+      if (account.message !== body) throw new Error(`${title} not created`);
+    }
+
+    await program.methods
+      .deleteEntries(batch.map(({ title }) => title))
+      .accounts({ userJournal: userJournalPda, owner: wallet.publicKey })
+      .remainingAccounts([
+        ...batch.map(({ title }) => writable(entryPda(title)[0])),
+        ...batch.map(({ title }) => writable(transferPda(entryPda(title)[0])[0])),
+        ...pagesFor(batch.length).map(writable),
+      ])
+      .rpc();
+    for (const { title } of batch) {
+      if (await program.account.journalEntry.fetchNullable(entryPda(title)[0])) throw new Error(`${title} not deleted`);
+    }
+    console.log("✅ Batch Passed");
+  });
+
+  it("25. Forget Me Closes Every Entry And The Journal", async () => {
+    const leaver = web3.Keypair.generate();
+    await program.provider.connection.confirmTransaction(
+      await program.provider.connection.requestAirdrop(leaver.publicKey, web3.LAMPORTS_PER_SOL)
+    );
+    const pdaOf = (seeds: Buffer[]) => anchor.web3.PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const leaverJournal = pdaOf([Buffer.from("USER_JOURNAL"), leaver.publicKey.toBuffer()]);
+    const leaverPage = pdaOf([
+      Buffer.from("JOURNAL_PAGE"),
+      leaver.publicKey.toBuffer(),
+      new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+    ]);
+    const leaverEntry = (entryTitle: string) =>
+      pdaOf([
+        Buffer.from("JOURNAL_ENTRY"),
+        createHash("sha256").update(entryTitle.toLowerCase()).digest(),
+        leaver.publicKey.toBuffer(),
+      ]);
+    const titles = ["First day", "Last day"];
+    for (const entryTitle of titles) {
+      await program.methods
+        .createEntry(entryTitle, "So long", [])
+        .accounts({
+          journalEntry: leaverEntry(entryTitle),
+          userJournal: leaverJournal,
+          journalPage: leaverPage,
+          owner: leaver.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([leaver])
+        .rpc();
+    }
+    const writable = (pubkey: web3.PublicKey) => ({ pubkey, isWritable: true, isSigner: false });
+
+    // One entry per call, as a client resuming across transactions would
+    for (const entryTitle of titles) {
+      await program.methods
+        .closeAllEntries(new anchor.BN(0), 1)
+        .accounts({
+          userJournal: leaverJournal,
+          journalPage: leaverPage,
+          owner: leaver.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          writable(leaverEntry(entryTitle)),
+          writable(transferPda(leaverEntry(entryTitle))[0]),
+        ])
+        .signers([leaver])
+        .rpc();
+    }
+    if ((await program.account.userJournal.fetch(leaverJournal)).entryCount.toNumber() !== 0)
+      throw new Error("Entries left behind");
+
+    await program.methods
+      .closeJournal(1)
+      .accounts({ userJournal: leaverJournal, owner: leaver.publicKey })
+      .remainingAccounts([writable(leaverPage)])
+      .signers([leaver])
+      .rpc();
+    if (await program.account.userJournal.fetchNullable(leaverJournal)) throw new Error("Journal not closed");
+    if (await program.account.journalPage.fetchNullable(leaverPage)) throw new Error("Index page not closed");
+    console.log("✅ Forget Me Passed");
+  });
 });